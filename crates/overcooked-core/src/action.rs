@@ -1,21 +1,37 @@
-use std::{collections::HashMap, error::Error, pin::Pin, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{
     action::action_template_executor::{SimpleActionExecutor, SimpleActionTemplateExecutor},
     actor::{
         self, ActorBase, actor_factory::ActorFactory, actor_state_extractor::ActorStateExtractor,
+        local_state::LocalState,
     },
     global_state::GlobalState,
 };
 
+mod action_output;
 mod action_template_executor;
+mod choice;
 mod lambda_proxy;
 
+pub use action_output::ActionOutput;
+pub use choice::choose;
+
 pub type IntransitiveAction = Arc<
     dyn Fn(
             Arc<dyn ActorBase>,
         ) -> Pin<
-            Box<dyn Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send + 'static>,
+            Box<
+                dyn Future<Output = Result<ActionOutput, Box<dyn Error + Send + Sync>>>
+                    + Send
+                    + 'static,
+            >,
         > + Send
         + Sync,
 >;
@@ -25,11 +41,41 @@ pub type TransitiveAction = Arc<
             Arc<dyn ActorBase>,
             Arc<dyn ActorBase>,
         ) -> Pin<
-            Box<dyn Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send + 'static>,
+            Box<
+                dyn Future<Output = Result<ActionOutput, Box<dyn Error + Send + Sync>>>
+                    + Send
+                    + 'static,
+            >,
         > + Send
         + Sync,
 >;
 
+pub type MultiReceiverAction = Arc<
+    dyn Fn(
+            Arc<dyn ActorBase>,
+            Vec<Arc<dyn ActorBase>>,
+        ) -> Pin<
+            Box<
+                dyn Future<Output = Result<ActionOutput, Box<dyn Error + Send + Sync>>>
+                    + Send
+                    + 'static,
+            >,
+        > + Send
+        + Sync,
+>;
+
+/// Decides whether an action is enabled from the local states of its
+/// performer and of its receivers, in the order of
+/// [`ActionTemplate::receiver_ids`].
+pub type ActionGuard = Arc<dyn Fn(&LocalState, &[LocalState]) -> bool + Send + Sync>;
+
+/// Makes the action taken with the given argument.
+pub type ArgumentBinding = Arc<dyn Fn(i64) -> ActionType + Send + Sync>;
+
+/// Intransitive and transitive actions could be told as multi receiver ones
+/// with no or a single receiver. They are kept apart since most actions are
+/// one of them, and their closures are handed the actors they take rather
+/// than a `Vec` whose length they would have to rely on.
 #[derive(Clone)]
 pub enum ActionType {
     Intransitive(IntransitiveAction),
@@ -37,41 +83,259 @@ pub enum ActionType {
         receiver_id: actor::Id,
         action: TransitiveAction,
     },
+    /// Involves any number of receivers at once, such as a coordinator
+    /// broadcasting a decision to every participant. The receivers are handed
+    /// to the action in the order of `receiver_ids`.
+    MultiReceiver {
+        receiver_ids: Vec<actor::Id>,
+        action: MultiReceiverAction,
+    },
+    /// Stands for one action per value of `arguments`, such as adding any
+    /// volume from 1 to 5 to a jug. Transition computers expand it with
+    /// [`ActionTemplate::expand`] before executing anything.
+    Parameterized {
+        arguments: Vec<i64>,
+        bind: ArgumentBinding,
+    },
 }
 
+/// An action as taken by a given performer. Templates built by hand rather
+/// than through [`ActionTemplate::new`] are checked for actors involved more
+/// than once when they are executed.
 #[derive(Clone)]
 pub struct ActionTemplate {
     pub performer_id: actor::Id,
     pub label: String,
     pub action_type: ActionType,
+    /// Disabled actions are not executed at all, so they lead to no
+    /// transition. Actions without a guard are always enabled.
+    pub guard: Option<ActionGuard>,
+    /// Overrides the policy of the model for this action.
+    pub failed_action_policy: Option<FailedActionPolicy>,
+    /// The argument the action is taken with, if it was expanded from a
+    /// parameterized one.
+    pub argument: Option<i64>,
+}
+
+/// What becomes of an action that returned an error, whose actors may have
+/// been partially mutated before it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailedActionPolicy {
+    /// The failed transition leads to the state the actors were left in.
+    #[default]
+    KeepState,
+    /// The failed transition leads back to the state the action was taken
+    /// from, as if nothing had happened.
+    RollBack,
+    /// No transition is recorded, as if the action had not been enabled.
+    Drop,
 }
 
 #[mockall::automock]
 #[async_trait::async_trait]
 pub trait ActionTemplateExecutor {
-    async fn execute(&self, template: ActionTemplate, global_state: GlobalState)
-    -> ExecutionResult;
+    /// Executes the action once per combination of the options it
+    /// [`choose`]s from, each leading to its own result.
+    async fn execute(
+        &self,
+        template: ActionTemplate,
+        global_state: GlobalState,
+    ) -> Vec<ExecutionResult>;
 }
 
+/// How the execution of an action ended.
 #[derive(Debug, Clone)]
-pub struct ActionResult(pub Option<Arc<dyn Error + Send + Sync>>);
+pub enum ActionResult {
+    Succeeded,
+    /// The action returned an error.
+    Failed(Arc<dyn Error + Send + Sync>),
+    /// The action panicked with this message.
+    Panicked(String),
+    /// The action did not complete within this timeout and was aborted. Like
+    /// a panic, it leads back to the state the action was taken from, whatever
+    /// the failed action policy.
+    TimedOut(Duration),
+}
+
+impl ActionResult {
+    pub fn is_success(&self) -> bool {
+        matches!(self, ActionResult::Succeeded)
+    }
+
+    /// Whether the action panicked or timed out, which is a fault of the
+    /// actors rather than an outcome of the model.
+    pub fn is_fault(&self) -> bool {
+        matches!(self, ActionResult::Panicked(_) | ActionResult::TimedOut(_))
+    }
+}
+
+/// Failures are told apart by the message of their error.
+impl PartialEq for ActionResult {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ActionResult::Succeeded, ActionResult::Succeeded) => true,
+            (ActionResult::Failed(error), ActionResult::Failed(other_error)) => {
+                error.to_string() == other_error.to_string()
+            }
+            (ActionResult::Panicked(message), ActionResult::Panicked(other_message)) => {
+                message == other_message
+            }
+            (ActionResult::TimedOut(timeout), ActionResult::TimedOut(other_timeout)) => {
+                timeout == other_timeout
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ActionResult {}
+
+impl std::hash::Hash for ActionResult {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            ActionResult::Succeeded => {}
+            ActionResult::Failed(error) => error.to_string().hash(state),
+            ActionResult::Panicked(message) => message.hash(state),
+            ActionResult::TimedOut(timeout) => timeout.hash(state),
+        }
+    }
+}
+
+impl std::fmt::Display for ActionResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionResult::Succeeded => write!(f, "succeeded"),
+            ActionResult::Failed(error) => write!(f, "{error}"),
+            ActionResult::Panicked(message) => write!(f, "panicked: {message}"),
+            ActionResult::TimedOut(timeout) => write!(f, "timed out after {timeout:?}"),
+        }
+    }
+}
 
 pub struct ExecutionResult {
     pub action_result: ActionResult,
+    /// What the action returned, unless it failed.
+    pub action_output: Option<ActionOutput>,
     pub global_states: GlobalState,
 }
 
+/// How the actions of a model are executed, by exhaustive explorations and
+/// simulations alike.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExecutionConfig {
+    /// Applies to the actions that do not set their own.
+    pub failed_action_policy: FailedActionPolicy,
+    /// Actions still running after this long are aborted, which is reported
+    /// as a fault.
+    pub action_timeout: Option<Duration>,
+}
+
 pub fn create_executor(
     actor_factories: HashMap<actor::Id, Box<dyn ActorFactory>>,
     actor_state_extractors: HashMap<actor::Id, Box<dyn ActorStateExtractor>>,
-) -> Box<dyn ActionTemplateExecutor + Sync> {
+    config: &ExecutionConfig,
+) -> Box<dyn ActionTemplateExecutor + Send + Sync> {
     Box::new(SimpleActionTemplateExecutor {
-        action_executor: SimpleActionExecutor,
+        action_executor: SimpleActionExecutor {
+            action_timeout: config.action_timeout,
+        },
         actor_factories,
         actor_state_extractors,
     })
 }
 
+impl ActionTemplate {
+    /// An action that is always enabled, follows the failed action policy of
+    /// the model and takes no argument.
+    ///
+    /// # Panics
+    ///
+    /// When an actor is listed twice among the receivers, or the performer
+    /// among them, as each actor only has a single state to be acted on.
+    pub fn new(performer_id: actor::Id, label: String, action_type: ActionType) -> Self {
+        let action_template = Self {
+            performer_id,
+            label,
+            action_type,
+            guard: None,
+            failed_action_policy: None,
+            argument: None,
+        };
+        action_template.check_receivers();
+        action_template
+    }
+
+    pub(crate) fn check_receivers(&self) {
+        let mut actor_ids = HashSet::new();
+        for actor_id in self.actor_ids() {
+            assert!(
+                actor_ids.insert(actor_id),
+                "{} is involved more than once in action {} of {}",
+                actor_id.0,
+                self.label,
+                self.performer_id.0
+            );
+        }
+    }
+
+    /// Every actor the action is performed on, which is none for
+    /// intransitive actions and a single one for transitive actions.
+    pub fn receiver_ids(&self) -> Vec<&actor::Id> {
+        match &self.action_type {
+            ActionType::Intransitive(_) | ActionType::Parameterized { .. } => vec![],
+            ActionType::Transitive { receiver_id, .. } => vec![receiver_id],
+            ActionType::MultiReceiver { receiver_ids, .. } => receiver_ids.iter().collect(),
+        }
+    }
+
+    /// One action per argument of a parameterized action, labelled with the
+    /// argument it is taken with, e.g. `add(3)`. Other actions are left as
+    /// they are.
+    ///
+    /// # Panics
+    ///
+    /// When an action is bound to receivers [`ActionTemplate::new`] rejects.
+    pub fn expand(&self) -> Vec<ActionTemplate> {
+        let ActionType::Parameterized { arguments, bind } = &self.action_type else {
+            return vec![self.clone()];
+        };
+        arguments
+            .iter()
+            .flat_map(|argument| {
+                let action_template = ActionTemplate {
+                    label: format!("{}({argument})", self.label),
+                    action_type: bind(*argument),
+                    argument: Some(*argument),
+                    ..self.clone()
+                };
+                action_template.check_receivers();
+                action_template.expand()
+            })
+            .collect()
+    }
+
+    /// Every actor involved in the action, starting with its performer.
+    pub fn actor_ids(&self) -> Vec<&actor::Id> {
+        std::iter::once(&self.performer_id)
+            .chain(self.receiver_ids())
+            .collect()
+    }
+
+    pub fn is_enabled(&self, global_state: &GlobalState) -> bool {
+        self.guard.as_ref().is_none_or(|guard| {
+            guard(
+                &global_state.get_local_state(&self.performer_id),
+                &self
+                    .receiver_ids()
+                    .into_iter()
+                    .map(|receiver_id| global_state.get_local_state(receiver_id))
+                    .collect::<Vec<_>>(),
+            )
+        })
+    }
+}
+
 impl std::cmp::PartialEq for ActionTemplate {
     fn eq(&self, other: &Self) -> bool {
         self.performer_id == other.performer_id
@@ -88,6 +352,26 @@ impl std::cmp::PartialEq for ActionTemplate {
                         action: _,
                     },
                 ) => self_receiver_id == other_receiver_id,
+                (
+                    ActionType::MultiReceiver {
+                        receiver_ids: self_receiver_ids,
+                        action: _,
+                    },
+                    ActionType::MultiReceiver {
+                        receiver_ids: other_receiver_ids,
+                        action: _,
+                    },
+                ) => self_receiver_ids == other_receiver_ids,
+                (
+                    ActionType::Parameterized {
+                        arguments: self_arguments,
+                        bind: _,
+                    },
+                    ActionType::Parameterized {
+                        arguments: other_arguments,
+                        bind: _,
+                    },
+                ) => self_arguments == other_arguments,
                 _ => false,
             }
     }
@@ -105,6 +389,11 @@ impl std::hash::Hash for ActionTemplate {
                 receiver_id,
                 action: _,
             } => receiver_id.hash(state),
+            ActionType::MultiReceiver {
+                receiver_ids,
+                action: _,
+            } => receiver_ids.hash(state),
+            ActionType::Parameterized { arguments, bind: _ } => arguments.hash(state),
         }
     }
 }
@@ -114,6 +403,8 @@ impl std::fmt::Debug for ActionTemplate {
         f.debug_struct("ActionTemplate")
             .field("performer_id", &self.performer_id)
             .field("label", &self.label)
+            .field("guard", &self.guard.is_some())
+            .field("failed_action_policy", &self.failed_action_policy)
             .finish()
     }
 }
@@ -127,25 +418,28 @@ mod tests {
     };
 
     use crate::{
-        action::ActionType,
+        action::{ActionOutput, ActionType},
         actor::{self, ActorBase},
     };
 
+    use test_case::test_case;
+
     use super::ActionTemplate;
 
     #[test]
     fn action_template_can_be_added_to_a_set() {
-        let _ = HashSet::from([(ActionTemplate {
-            performer_id: actor::Id("actor".to_string()),
-            label: SOME_INTRANSITIVE_ACTION.clone().to_string(),
-            action_type: ActionType::Intransitive(Arc::new(|actor| {
+        let _ = HashSet::from([(ActionTemplate::new(
+            actor::Id("actor".to_string()),
+            SOME_INTRANSITIVE_ACTION.clone().to_string(),
+            ActionType::Intransitive(Arc::new(|actor| {
                 Box::pin(proxy_for_intransitive_action(actor))
             })),
-        })]);
+        ))]);
     }
 
     static ACTOR_1_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_1".to_string()));
     static ACTOR_2_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_2".to_string()));
+    static ACTOR_3_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_3".to_string()));
 
     static SOME_INTRANSITIVE_ACTION: LazyLock<String> =
         LazyLock::new(|| "some intransitive_action".to_string());
@@ -160,97 +454,211 @@ mod tests {
     fn action_template_eq_works() {
         // diffferent action type
         assert_ne!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            ),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            )
         );
 
         // Intransitive
         assert_eq!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            ),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            )
         );
 
         assert_ne!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_OTHER_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            ),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_OTHER_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            )
         );
 
         assert_ne!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_2_ID.clone(),
-                label: SOME_INTRANSITIVE_ACTION.clone(),
-                action_type: intransitive_action_type(),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            ),
+            ActionTemplate::new(
+                ACTOR_2_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            )
         );
 
         // Transitive
         assert_eq!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            ),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            )
         );
 
         assert_ne!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
-            },
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_OTHER_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
-            }
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            ),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_OTHER_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            )
         );
 
         assert_ne!(
-            ActionTemplate {
-                performer_id: ACTOR_1_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_2_ID.clone()),
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            ),
+            ActionTemplate::new(
+                ACTOR_2_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_1_ID.clone())
+            )
+        );
+    }
+
+    #[test]
+    fn receiver_ids_works() {
+        assert_eq!(
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            )
+            .receiver_ids(),
+            Vec::<&actor::Id>::new()
+        );
+        assert_eq!(
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            )
+            .receiver_ids(),
+            vec![&*ACTOR_2_ID]
+        );
+        assert_eq!(
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                multi_receiver_action_type(vec![ACTOR_3_ID.clone(), ACTOR_2_ID.clone()])
+            )
+            .receiver_ids(),
+            vec![&*ACTOR_3_ID, &*ACTOR_2_ID]
+        );
+    }
+
+    #[test]
+    fn actor_ids_works() {
+        assert_eq!(
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_INTRANSITIVE_ACTION.clone(),
+                intransitive_action_type()
+            )
+            .actor_ids(),
+            vec![&*ACTOR_1_ID]
+        );
+        assert_eq!(
+            ActionTemplate::new(
+                ACTOR_1_ID.clone(),
+                SOME_TRANSITIVE_ACTION.clone(),
+                transitive_action_type(ACTOR_2_ID.clone())
+            )
+            .actor_ids(),
+            vec![&*ACTOR_1_ID, &*ACTOR_2_ID]
+        );
+    }
+
+    #[test]
+    fn expand_works() {
+        let parameterized = ActionTemplate::new(
+            ACTOR_1_ID.clone(),
+            "add".to_string(),
+            ActionType::Parameterized {
+                arguments: (1..=3).collect(),
+                bind: Arc::new(|argument| {
+                    if argument < 3 {
+                        intransitive_action_type()
+                    } else {
+                        transitive_action_type(ACTOR_2_ID.clone())
+                    }
+                }),
             },
-            ActionTemplate {
-                performer_id: ACTOR_2_ID.clone(),
-                label: SOME_TRANSITIVE_ACTION.clone(),
-                action_type: transitive_action_type(ACTOR_1_ID.clone()),
-            }
         );
+
+        let expanded = parameterized.expand();
+
+        assert_eq!(
+            expanded
+                .iter()
+                .map(|action_template| (
+                    action_template.label.as_str(),
+                    action_template.argument,
+                    action_template.receiver_ids()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("add(1)", Some(1), vec![]),
+                ("add(2)", Some(2), vec![]),
+                ("add(3)", Some(3), vec![&*ACTOR_2_ID])
+            ]
+        );
+        assert_eq!(expanded[0].expand(), vec![expanded[0].clone()]);
+    }
+
+    #[test_case(transitive_action_type(ACTOR_1_ID.clone()) ; "performer as the receiver")]
+    #[test_case(multi_receiver_action_type(vec![ACTOR_2_ID.clone(), ACTOR_1_ID.clone()]) ; "performer among the receivers")]
+    #[test_case(multi_receiver_action_type(vec![ACTOR_2_ID.clone(), ACTOR_2_ID.clone()]) ; "duplicate receivers")]
+    #[should_panic(expected = "is involved more than once")]
+    fn actors_involved_more_than_once_are_rejected(action_type: ActionType) {
+        ActionTemplate::new(ACTOR_1_ID.clone(), "act".to_string(), action_type);
+    }
+
+    #[test]
+    #[should_panic(expected = "is involved more than once in action add(2)")]
+    fn actors_involved_more_than_once_are_rejected_once_bound() {
+        ActionTemplate::new(
+            ACTOR_1_ID.clone(),
+            "add".to_string(),
+            ActionType::Parameterized {
+                arguments: vec![1, 2],
+                bind: Arc::new(|argument| {
+                    multi_receiver_action_type(vec![ACTOR_2_ID.clone(); argument as usize])
+                }),
+            },
+        )
+        .expand();
     }
 
     fn intransitive_action_type() -> ActionType {
@@ -259,6 +667,13 @@ mod tests {
         }))
     }
 
+    fn multi_receiver_action_type(receiver_ids: Vec<actor::Id>) -> ActionType {
+        ActionType::MultiReceiver {
+            receiver_ids,
+            action: Arc::new(|_, _| Box::pin(async { Ok(ActionOutput::new(())) })),
+        }
+    }
+
     fn transitive_action_type(receiver_id: actor::Id) -> ActionType {
         ActionType::Transitive {
             receiver_id,
@@ -270,14 +685,14 @@ mod tests {
 
     async fn proxy_for_intransitive_action(
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 
     async fn proxy_for_transitive_action(
         _: Arc<dyn ActorBase>,
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 }
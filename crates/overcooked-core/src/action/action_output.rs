@@ -0,0 +1,102 @@
+use std::{any::Any, sync::Arc};
+
+use dyn_clone::DynClone;
+
+use crate::derives::{
+    dyn_hash::DynHash, dyn_ord::DynOrd, dyn_partial_eq::DynPartialEq,
+    dyn_partial_ord::DynPartialOrd,
+};
+
+/// A value returned by an actor method, such as the available space of a
+/// jug. Unlike [`ActorState`](crate::actor::actor_state::ActorState), it is
+/// implemented for every type that qualifies, `()` included.
+pub trait OutputValue:
+    Any + Send + Sync + DynPartialEq + DynPartialOrd + DynOrd + DynHash + DynClone + std::fmt::Debug
+{
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> OutputValue for T
+where
+    T: Any + Send + Sync + PartialEq + PartialOrd + Ord + std::hash::Hash + Clone + std::fmt::Debug,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// What a successful action returned, compared, ordered and hashed by value
+/// the same way as a [`LocalState`](crate::actor::local_state::LocalState).
+#[derive(Debug, Clone)]
+pub struct ActionOutput {
+    pub value: Arc<dyn OutputValue>,
+}
+
+impl ActionOutput {
+    pub fn new(value: impl OutputValue) -> Self {
+        Self {
+            value: Arc::new(value),
+        }
+    }
+
+    /// The value returned, if it is a `T`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        OutputValue::as_any(self.value.as_ref()).downcast_ref()
+    }
+}
+
+impl PartialEq for ActionOutput {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.dyn_eq(other.value.as_ref())
+    }
+}
+
+impl Eq for ActionOutput {}
+
+impl PartialOrd for ActionOutput {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ActionOutput {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.dyn_cmp(other.value.as_ref())
+    }
+}
+
+impl std::hash::Hash for ActionOutput {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.dyn_hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cmp::Ordering, collections::HashSet};
+
+    use super::ActionOutput;
+
+    #[test]
+    fn outputs_are_compared_by_value() {
+        assert_eq!(ActionOutput::new(6u8), ActionOutput::new(6u8));
+        assert_ne!(ActionOutput::new(6u8), ActionOutput::new(7u8));
+        assert_ne!(ActionOutput::new(6u8), ActionOutput::new(6u16));
+        assert_eq!(
+            ActionOutput::new(6u8).cmp(&ActionOutput::new(7u8)),
+            Ordering::Less
+        );
+        assert_eq!(
+            HashSet::from([ActionOutput::new(()), ActionOutput::new(())]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn downcast_ref_works() {
+        let output = ActionOutput::new(6u8);
+
+        assert_eq!(output.downcast_ref::<u8>(), Some(&6));
+        assert_eq!(output.downcast_ref::<u16>(), None);
+    }
+}
@@ -1,9 +1,12 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
 use crate::{
     action::{
-        ActionResult, ActionTemplate, ActionTemplateExecutor, ExecutionResult, IntransitiveAction,
-        TransitiveAction,
+        ActionOutput, ActionResult, ActionTemplate, ActionTemplateExecutor, ExecutionResult,
+        IntransitiveAction, MultiReceiverAction, TransitiveAction,
     },
     actor::{
         self, ActorBase, actor_factory::ActorFactory, actor_state_extractor::ActorStateExtractor,
@@ -12,7 +15,7 @@ use crate::{
     global_state::GlobalState,
 };
 
-use super::ActionType;
+use super::{ActionType, choice::Choices};
 pub(super) use action_executor::SimpleActionExecutor;
 
 mod action;
@@ -20,8 +23,8 @@ mod action_executor;
 
 #[mockall::automock]
 #[async_trait::async_trait]
-pub(super) trait ActionExecutor: Sync {
-    async fn execute(&self, action: Action) -> ActionResult;
+pub(super) trait ActionExecutor: Send + Sync {
+    async fn execute(&self, action: Action) -> (ActionResult, Option<ActionOutput>);
 }
 
 pub(super) enum Action {
@@ -34,6 +37,11 @@ pub(super) enum Action {
         receiver: Arc<dyn ActorBase>,
         action: TransitiveAction,
     },
+    MultiReceiver {
+        performer: Arc<dyn ActorBase>,
+        receivers: Vec<Arc<dyn ActorBase>>,
+        action: MultiReceiverAction,
+    },
 }
 
 pub(super) struct SimpleActionTemplateExecutor<AE>
@@ -54,55 +62,84 @@ where
         &self,
         template: ActionTemplate,
         global_states: GlobalState,
-    ) -> ExecutionResult {
+    ) -> Vec<ExecutionResult> {
+        template.check_receivers();
+        let mut execution_results = Vec::new();
+        let mut pending = VecDeque::from([Choices::default()]);
+        while let Some(choices) = pending.pop_front() {
+            let (execution_result, choices) = self
+                .execute_once(template.clone(), &global_states, choices)
+                .await;
+            pending.extend(choices.alternatives());
+            execution_results.push(execution_result);
+        }
+        execution_results
+    }
+}
+
+impl<AE> SimpleActionTemplateExecutor<AE>
+where
+    AE: ActionExecutor,
+{
+    /// Executes the action on freshly restored actors, picking the options
+    /// of `choices`.
+    async fn execute_once(
+        &self,
+        template: ActionTemplate,
+        global_states: &GlobalState,
+        choices: Choices,
+    ) -> (ExecutionResult, Choices) {
+        // Intransitive and transitive actions are restored and extracted the
+        // same way as actions with any number of receivers.
         let performer = self
-            .restore_actor(&template.performer_id, &global_states)
+            .restore_actor(&template.performer_id, global_states)
             .await;
+        let receiver_ids: Vec<actor::Id> = template.receiver_ids().into_iter().cloned().collect();
+        let mut receivers = Vec::with_capacity(receiver_ids.len());
+        for receiver_id in &receiver_ids {
+            receivers.push(self.restore_actor(receiver_id, global_states).await);
+        }
 
-        let mut updated_global_states = global_states.clone();
-        let action_result = match template.action_type {
-            ActionType::Intransitive(action) => {
-                let action_result = self
-                    .action_executor
-                    .execute(Action::Intransitive {
-                        performer: performer.clone(),
-                        action,
-                    })
-                    .await;
-
-                action_result
-            }
-            ActionType::Transitive {
-                receiver_id,
+        let action = match template.action_type {
+            ActionType::Intransitive(action) => Action::Intransitive {
+                performer: performer.clone(),
                 action,
-            } => {
-                let receiver = self.restore_actor(&receiver_id, &global_states).await;
-                let action_result = self
-                    .action_executor
-                    .execute(Action::Transitive {
-                        performer: performer.clone(),
-                        receiver: receiver.clone(),
-                        action,
-                    })
-                    .await;
-
-                updated_global_states.insert_local_state(
-                    receiver_id.clone(),
-                    self.extract_state(&receiver_id, receiver).await,
-                );
-                action_result
+            },
+            ActionType::Transitive { action, .. } => Action::Transitive {
+                performer: performer.clone(),
+                receiver: receivers[0].clone(),
+                action,
+            },
+            ActionType::MultiReceiver { action, .. } => Action::MultiReceiver {
+                performer: performer.clone(),
+                receivers: receivers.clone(),
+                action,
+            },
+            ActionType::Parameterized { .. } => {
+                unreachable!("parameterized actions are expanded before being executed")
             }
         };
+        let ((action_result, action_output), choices) =
+            choices.run(self.action_executor.execute(action)).await;
 
+        let mut updated_global_states = global_states.clone();
+        for (receiver_id, receiver) in receiver_ids.into_iter().zip(receivers) {
+            let local_state = self.extract_state(&receiver_id, receiver).await;
+            updated_global_states.insert_local_state(receiver_id, local_state);
+        }
         updated_global_states.insert_local_state(
             template.performer_id.clone(),
             self.extract_state(&template.performer_id, performer).await,
         );
 
-        ExecutionResult {
-            action_result,
-            global_states: updated_global_states,
-        }
+        (
+            ExecutionResult {
+                action_result,
+                action_output,
+                global_states: updated_global_states,
+            },
+            choices,
+        )
     }
 }
 
@@ -139,15 +176,16 @@ mod tests {
     use std::{
         collections::{BTreeMap, HashMap},
         error::Error,
-        sync::{Arc, LazyLock},
+        sync::{Arc, LazyLock, atomic::Ordering},
     };
 
     use mockall::predicate::eq;
 
     use crate::{
         action::{
-            ActionResult, ActionTemplate, ActionTemplateExecutor, ActionType,
+            ActionOutput, ActionResult, ActionTemplate, ActionTemplateExecutor, ActionType,
             action_template_executor::{Action, MockActionExecutor, SimpleActionTemplateExecutor},
+            choice::choose,
         },
         actor::{
             self, ActorBase,
@@ -162,6 +200,7 @@ mod tests {
 
     static ACTOR_1_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_1".to_string()));
     static ACTOR_2_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_2".to_string()));
+    static ACTOR_3_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_3".to_string()));
 
     #[tokio::test]
     async fn intransitive_action_template_execution_works() {
@@ -193,7 +232,7 @@ mod tests {
                 action: Arc::new(|actor| Box::pin(proxy_for_intransitive_action(actor))),
             }))
             .once()
-            .return_once(|_| ActionResult(None));
+            .return_once(|_| (ActionResult::Succeeded, Some(ActionOutput::new(()))));
 
         let actor_state_updated_clone = actor_state_updated.clone();
         actor_1_state_extractor
@@ -214,20 +253,22 @@ mod tests {
             )]),
         };
 
-        let execution_result = executor
+        let execution_results = executor
             .execute(
-                ActionTemplate {
-                    performer_id: ACTOR_1_ID.clone(),
-                    label: "some_intransitive_action".to_string(),
-                    action_type: ActionType::Intransitive(Arc::new(|actor| {
+                ActionTemplate::new(
+                    ACTOR_1_ID.clone(),
+                    "some_intransitive_action".to_string(),
+                    ActionType::Intransitive(Arc::new(|actor| {
                         Box::pin(proxy_for_intransitive_action(actor))
                     })),
-                },
+                ),
                 global_state,
             )
             .await;
 
-        assert!(execution_result.action_result.0.is_none());
+        assert_eq!(execution_results.len(), 1);
+        let execution_result = &execution_results[0];
+        assert!(execution_result.action_result.is_success());
         assert_eq!(
             execution_result.global_states,
             GlobalState::new(BTreeMap::from([(
@@ -292,7 +333,7 @@ mod tests {
                 }),
             }))
             .once()
-            .return_once(|_| ActionResult(None));
+            .return_once(|_| (ActionResult::Succeeded, Some(ActionOutput::new(()))));
 
         let actor_1_state_updated_clone = actor_1_state_updated.clone();
         actor_1_state_extractor
@@ -332,23 +373,25 @@ mod tests {
             ]),
         };
 
-        let execution_result = executor
+        let execution_results = executor
             .execute(
-                ActionTemplate {
-                    performer_id: ACTOR_1_ID.clone(),
-                    label: "some_transitive_action".to_string(),
-                    action_type: ActionType::Transitive {
+                ActionTemplate::new(
+                    ACTOR_1_ID.clone(),
+                    "some_transitive_action".to_string(),
+                    ActionType::Transitive {
                         receiver_id: ACTOR_2_ID.clone(),
                         action: Arc::new(|actor_1, actor_2| {
                             Box::pin(proxy_for_transitive_action(actor_1, actor_2))
                         }),
                     },
-                },
+                ),
                 global_state,
             )
             .await;
 
-        assert!(execution_result.action_result.0.is_none());
+        assert_eq!(execution_results.len(), 1);
+        let execution_result = &execution_results[0];
+        assert!(execution_result.action_result.is_success());
         assert_eq!(
             execution_result.global_states,
             GlobalState::new(BTreeMap::from([
@@ -368,6 +411,232 @@ mod tests {
         );
     }
 
+    /// Actor 1 performs the action on actors 2 and 3, both [`TestActor2`]s.
+    #[tokio::test]
+    async fn multi_receiver_action_template_execution_works() {
+        let performer: Arc<dyn ActorBase> = Arc::new(TestActor1::new(10));
+        let receivers: Vec<Arc<dyn ActorBase>> =
+            vec![Arc::new(TestActor2::new(20)), Arc::new(TestActor2::new(30))];
+        let actor_2_state = |value| -> Arc<dyn ActorState> { Arc::new(TestActor2State { value }) };
+        let actors = [
+            (
+                ACTOR_1_ID.clone(),
+                performer.clone(),
+                Arc::new(TestActor1State { value: 10 }) as Arc<dyn ActorState>,
+                Arc::new(TestActor1State { value: 11 }) as Arc<dyn ActorState>,
+            ),
+            (
+                ACTOR_2_ID.clone(),
+                receivers[0].clone(),
+                actor_2_state(20),
+                actor_2_state(21),
+            ),
+            (
+                ACTOR_3_ID.clone(),
+                receivers[1].clone(),
+                actor_2_state(30),
+                actor_2_state(31),
+            ),
+        ];
+
+        let mut actor_factories: HashMap<actor::Id, Box<dyn ActorFactory>> = HashMap::new();
+        let mut actor_state_extractors: HashMap<actor::Id, Box<dyn ActorStateExtractor>> =
+            HashMap::new();
+        for (actor_id, actor, original_state, updated_state) in actors.clone() {
+            let mut actor_factory = MockActorFactory::new();
+            actor_factory
+                .expect_restore_from_state()
+                .withf(move |actor_state| actor_state.dyn_eq(original_state.as_ref()))
+                .once()
+                .return_once(|_| actor);
+            actor_factories.insert(actor_id.clone(), Box::new(actor_factory));
+
+            let mut actor_state_extractor = MockActorStateExtractor::new();
+            actor_state_extractor
+                .expect_extract()
+                .once()
+                .return_once(|_| updated_state);
+            actor_state_extractors.insert(actor_id, Box::new(actor_state_extractor));
+        }
+
+        let mut action_executor = MockActionExecutor::new();
+        action_executor
+            .expect_execute()
+            .with(eq(Action::MultiReceiver {
+                performer,
+                receivers,
+                action: Arc::new(|_, _| Box::pin(async { Ok(ActionOutput::new(())) })),
+            }))
+            .once()
+            .return_once(|_| (ActionResult::Succeeded, Some(ActionOutput::new(()))));
+
+        let executor = SimpleActionTemplateExecutor {
+            action_executor,
+            actor_factories,
+            actor_state_extractors,
+        };
+
+        let execution_results = executor
+            .execute(
+                ActionTemplate::new(
+                    ACTOR_1_ID.clone(),
+                    "some_multi_receiver_action".to_string(),
+                    ActionType::MultiReceiver {
+                        receiver_ids: vec![ACTOR_2_ID.clone(), ACTOR_3_ID.clone()],
+                        action: Arc::new(|_, _| Box::pin(async { Ok(ActionOutput::new(())) })),
+                    },
+                ),
+                GlobalState::new(
+                    actors
+                        .iter()
+                        .map(|(actor_id, _, original_state, _)| {
+                            (
+                                actor_id.clone(),
+                                LocalState {
+                                    actor_state: original_state.clone(),
+                                },
+                            )
+                        })
+                        .collect(),
+                ),
+            )
+            .await;
+
+        assert_eq!(execution_results.len(), 1);
+        let execution_result = &execution_results[0];
+        assert!(execution_result.action_result.is_success());
+        assert_eq!(
+            execution_result.global_states,
+            GlobalState::new(
+                actors
+                    .into_iter()
+                    .map(|(actor_id, _, _, updated_state)| {
+                        (
+                            actor_id,
+                            LocalState {
+                                actor_state: updated_state,
+                            },
+                        )
+                    })
+                    .collect()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn action_is_executed_once_per_choice() {
+        let mut actor_factory = MockActorFactory::new();
+        actor_factory
+            .expect_restore_from_state()
+            .times(2)
+            .returning(|_| Arc::new(TestActor1::new(10)));
+
+        let mut action_executor = MockActionExecutor::new();
+        action_executor
+            .expect_execute()
+            .times(2)
+            .returning(|action| {
+                let Action::Intransitive { performer, .. } = action else {
+                    unreachable!();
+                };
+                ActorBase::as_any(performer.as_ref())
+                    .downcast_ref::<TestActor1>()
+                    .unwrap()
+                    .value
+                    .fetch_add(*choose(&[1, 2]), Ordering::Relaxed);
+                (ActionResult::Succeeded, Some(ActionOutput::new(())))
+            });
+
+        let mut actor_state_extractor = MockActorStateExtractor::new();
+        actor_state_extractor
+            .expect_extract()
+            .times(2)
+            .returning(|actor| {
+                Arc::new(TestActor1State {
+                    value: ActorBase::as_any(actor.as_ref())
+                        .downcast_ref::<TestActor1>()
+                        .unwrap()
+                        .get_value(),
+                })
+            });
+
+        let executor = SimpleActionTemplateExecutor {
+            action_executor,
+            actor_factories: HashMap::from([(
+                ACTOR_1_ID.clone(),
+                Box::new(actor_factory) as Box<dyn ActorFactory>,
+            )]),
+            actor_state_extractors: HashMap::from([(
+                ACTOR_1_ID.clone(),
+                Box::new(actor_state_extractor) as Box<dyn ActorStateExtractor>,
+            )]),
+        };
+
+        let execution_results = executor
+            .execute(
+                ActionTemplate::new(
+                    ACTOR_1_ID.clone(),
+                    "increase_by_one_or_two".to_string(),
+                    ActionType::Intransitive(Arc::new(|actor| {
+                        Box::pin(proxy_for_intransitive_action(actor))
+                    })),
+                ),
+                GlobalState::new(BTreeMap::from([(
+                    ACTOR_1_ID.clone(),
+                    LocalState {
+                        actor_state: Arc::new(TestActor1State { value: 10 }),
+                    },
+                )])),
+            )
+            .await;
+
+        assert_eq!(execution_results.len(), 2);
+        for (execution_result, expected_value) in execution_results.iter().zip([11, 12]) {
+            assert!(actor_1_state_having_value(
+                &execution_result
+                    .global_states
+                    .get_local_state(&ACTOR_1_ID)
+                    .actor_state,
+                expected_value
+            ));
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "is involved more than once")]
+    async fn templates_involving_an_actor_more_than_once_are_not_executed() {
+        let executor = SimpleActionTemplateExecutor {
+            action_executor: MockActionExecutor::new(),
+            actor_factories: HashMap::new(),
+            actor_state_extractors: HashMap::new(),
+        };
+        let mut template = ActionTemplate::new(
+            ACTOR_1_ID.clone(),
+            "some_transitive_action".to_string(),
+            ActionType::Intransitive(Arc::new(|actor| {
+                Box::pin(proxy_for_intransitive_action(actor))
+            })),
+        );
+        template.action_type = ActionType::Transitive {
+            receiver_id: ACTOR_1_ID.clone(),
+            action: Arc::new(|performer, receiver| {
+                Box::pin(proxy_for_transitive_action(performer, receiver))
+            }),
+        };
+
+        executor
+            .execute(
+                template,
+                GlobalState::new(BTreeMap::from([(
+                    ACTOR_1_ID.clone(),
+                    LocalState {
+                        actor_state: Arc::new(TestActor1State { value: 10 }),
+                    },
+                )])),
+            )
+            .await;
+    }
+
     fn actor_1_state_having_value(actor_state: &Arc<dyn ActorState>, expected_value: u8) -> bool {
         ActorState::as_any(actor_state.as_ref())
             .downcast_ref::<TestActor1State>()
@@ -402,14 +671,14 @@ mod tests {
 
     async fn proxy_for_intransitive_action(
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 
     async fn proxy_for_transitive_action(
         _: Arc<dyn ActorBase>,
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 }
@@ -25,6 +25,25 @@ impl PartialEq for Action {
                     action: _,
                 },
             ) => l_performer.dyn_eq(r_performer.as_ref()) && l_receiver.dyn_eq(r_receiver.as_ref()),
+            (
+                Self::MultiReceiver {
+                    performer: l_performer,
+                    receivers: l_receivers,
+                    action: _,
+                },
+                Self::MultiReceiver {
+                    performer: r_performer,
+                    receivers: r_receivers,
+                    action: _,
+                },
+            ) => {
+                l_performer.dyn_eq(r_performer.as_ref())
+                    && l_receivers.len() == r_receivers.len()
+                    && l_receivers
+                        .iter()
+                        .zip(r_receivers)
+                        .all(|(l_receiver, r_receiver)| l_receiver.dyn_eq(r_receiver.as_ref()))
+            }
             _ => false,
         }
     }
@@ -49,6 +68,15 @@ impl std::fmt::Debug for Action {
                 .field("performer", performer)
                 .field("receiver", receiver)
                 .finish(),
+            Self::MultiReceiver {
+                performer,
+                receivers,
+                action: _,
+            } => f
+                .debug_struct("MultiReceiver")
+                .field("performer", performer)
+                .field("receivers", receivers)
+                .finish(),
         }
     }
 }
@@ -60,6 +88,7 @@ mod tests {
     use test_case::test_case;
 
     use crate::{
+        action::ActionOutput,
         actor::ActorBase,
         test_utils::test_actors::{TestActor1, TestActor2},
     };
@@ -117,14 +146,14 @@ mod tests {
 
     async fn proxy_for_intransitive_action(
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 
     async fn proxy_for_transitive_action(
         _: Arc<dyn ActorBase>,
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 }
@@ -1,57 +1,125 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc, time::Duration};
 
-use super::{Action, ActionExecutor, ActionResult};
+use crate::action::choice;
 
-pub struct SimpleActionExecutor;
+use super::{Action, ActionExecutor, ActionOutput, ActionResult};
+
+/// Runs every action on a task of its own, so that an action that panics or
+/// never completes does not take the exploration down with it.
+pub struct SimpleActionExecutor {
+    /// Actions still running after this long are aborted.
+    pub action_timeout: Option<Duration>,
+}
 
 #[async_trait::async_trait]
 impl ActionExecutor for SimpleActionExecutor {
-    async fn execute(&self, action: Action) -> ActionResult {
-        if let Err(err) = match action {
+    async fn execute(&self, action: Action) -> (ActionResult, Option<ActionOutput>) {
+        let mut task = choice::spawn(match action {
             Action::Intransitive { performer, action } => action(performer),
             Action::Transitive {
                 performer,
                 receiver,
                 action,
             } => action(performer, receiver),
-        }
-        .await
-        {
-            ActionResult(Some(Arc::from(err)))
-        } else {
-            ActionResult(None)
+            Action::MultiReceiver {
+                performer,
+                receivers,
+                action,
+            } => action(performer, receivers),
+        });
+        let joined = match self.action_timeout {
+            Some(action_timeout) => match tokio::time::timeout(action_timeout, &mut task).await {
+                Ok(joined) => joined,
+                Err(_) => {
+                    // The actors are read once this returns, by which time
+                    // the action must have stopped acting on them.
+                    task.abort();
+                    let _ = task.await;
+                    return (ActionResult::TimedOut(action_timeout), None);
+                }
+            },
+            None => task.await,
+        };
+
+        match joined {
+            Ok(Ok(action_output)) => (ActionResult::Succeeded, Some(action_output)),
+            Ok(Err(err)) => (ActionResult::Failed(Arc::from(err)), None),
+            Err(join_error) => (
+                ActionResult::Panicked(
+                    join_error
+                        .try_into_panic()
+                        .map_or_else(|join_error| join_error.to_string(), panic_message),
+                ),
+                None,
+            ),
         }
     }
 }
 
+/// The message a task panicked with, which is a `&str` or a `String` unless
+/// the panic was raised with another payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "unknown panic payload".to_string(), ToString::to_string),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
 
     use crate::test_utils::test_actors::{
-        TestActor1, TestActor2, test_actor1_decrease_test_actor_2_value_by_one_test_actor2,
-        test_actor1_increase_inner_value_by_one,
+        TestActor1, TestActor2, test_actor1_decrease_every_test_actor_2_value_by_one_test_actor2,
+        test_actor1_decrease_test_actor_2_value_by_one_test_actor2,
+        test_actor1_increase_inner_value_by_one, test_actor1_read_value,
     };
 
     use super::*;
 
     #[tokio::test]
     async fn can_execute_intrasnsitive_action() {
-        let executor = SimpleActionExecutor;
+        let executor = SimpleActionExecutor {
+            action_timeout: None,
+        };
         let test_actor_1 = Arc::new(TestActor1::new(0));
         let action = Action::Intransitive {
             performer: test_actor_1.clone(),
             action: Arc::new(|actor| Box::pin(test_actor1_increase_inner_value_by_one(actor))),
         };
 
-        assert!(executor.execute(action).await.0.is_none());
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(action_result.is_success());
+        assert_eq!(action_output, Some(ActionOutput::new(())));
 
         assert_eq!(test_actor_1.get_value(), 1);
     }
 
+    #[tokio::test]
+    async fn value_returned_by_action_is_its_output() {
+        let executor = SimpleActionExecutor {
+            action_timeout: None,
+        };
+        let action = Action::Intransitive {
+            performer: Arc::new(TestActor1::new(3)),
+            action: Arc::new(|actor| Box::pin(test_actor1_read_value(actor))),
+        };
+
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(action_result.is_success());
+        assert_eq!(action_output.unwrap().downcast_ref::<u8>(), Some(&3));
+    }
+
     #[tokio::test]
     async fn can_execute_transitive_action() {
-        let executor = SimpleActionExecutor;
+        let executor = SimpleActionExecutor {
+            action_timeout: None,
+        };
         let test_actor_1 = Arc::new(TestActor1::new(0));
         let test_actor_2 = Arc::new(TestActor2::new(5));
         let action = Action::Transitive {
@@ -65,9 +133,104 @@ mod tests {
             }),
         };
 
-        assert!(executor.execute(action).await.0.is_none());
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(action_result.is_success());
+        assert_eq!(action_output, Some(ActionOutput::new(())));
 
         assert_eq!(test_actor_1.get_value(), 0);
         assert_eq!(test_actor_2.get_value(), 4);
     }
+
+    #[tokio::test]
+    async fn can_execute_multi_receiver_action() {
+        let executor = SimpleActionExecutor {
+            action_timeout: None,
+        };
+        let test_actor_1 = Arc::new(TestActor1::new(0));
+        let test_actor_2s = [Arc::new(TestActor2::new(5)), Arc::new(TestActor2::new(7))];
+        let action = Action::MultiReceiver {
+            performer: test_actor_1.clone(),
+            receivers: vec![test_actor_2s[0].clone(), test_actor_2s[1].clone()],
+            action: Arc::new(|action_performer, action_receivers| {
+                Box::pin(
+                    test_actor1_decrease_every_test_actor_2_value_by_one_test_actor2(
+                        action_performer,
+                        action_receivers,
+                    ),
+                )
+            }),
+        };
+
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(action_result.is_success());
+        assert_eq!(action_output, Some(ActionOutput::new(())));
+
+        assert_eq!(test_actor_1.get_value(), 0);
+        assert_eq!(test_actor_2s[0].get_value(), 4);
+        assert_eq!(test_actor_2s[1].get_value(), 6);
+    }
+
+    #[tokio::test]
+    async fn panics_are_captured() {
+        let executor = SimpleActionExecutor {
+            action_timeout: None,
+        };
+        let action = Action::Intransitive {
+            performer: Arc::new(TestActor2::new(0)),
+            action: Arc::new(|actor| Box::pin(test_actor1_increase_inner_value_by_one(actor))),
+        };
+
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(matches!(
+            action_result,
+            ActionResult::Panicked(message) if message.contains("unwrap")
+        ));
+        assert_eq!(action_output, None);
+    }
+
+    #[tokio::test]
+    async fn actions_are_aborted_once_timed_out() {
+        let timeout = Duration::from_millis(10);
+        let executor = SimpleActionExecutor {
+            action_timeout: Some(timeout),
+        };
+        let action = Action::Intransitive {
+            performer: Arc::new(TestActor1::new(0)),
+            action: Arc::new(|_| Box::pin(std::future::pending())),
+        };
+
+        let (action_result, action_output) = executor.execute(action).await;
+        assert!(matches!(action_result, ActionResult::TimedOut(elapsed) if elapsed == timeout));
+        assert_eq!(action_output, None);
+    }
+
+    #[tokio::test]
+    async fn timed_out_actions_have_stopped_once_executed() {
+        struct SetOnDrop(Arc<AtomicBool>);
+
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let executor = SimpleActionExecutor {
+            action_timeout: Some(Duration::from_millis(10)),
+        };
+        let dropped = Arc::new(AtomicBool::new(false));
+        let guard = dropped.clone();
+        let action = Action::Intransitive {
+            performer: Arc::new(TestActor1::new(0)),
+            action: Arc::new(move |_| {
+                let guard = SetOnDrop(guard.clone());
+                Box::pin(async move {
+                    let _guard = guard;
+                    std::future::pending().await
+                })
+            }),
+        };
+
+        executor.execute(action).await;
+        assert!(dropped.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,146 @@
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use tokio::task::JoinHandle;
+
+tokio::task_local! {
+    /// Shared rather than owned, so that the choices made by a task spawned
+    /// with [`spawn`] are kept even if it panics or is aborted.
+    static CHOICES: Arc<Mutex<Choices>>;
+}
+
+/// Picks one of `options`, letting actor code make an internal choice such as
+/// voting yes or no. While the model is explored, the action calling it is
+/// executed once per option, each execution leading to its own transition.
+///
+/// Actions must make the same choices from the same state, so that taking
+/// every option of a choice leads to the same later choices. Outside of an
+/// execution of an action template, e.g. in unit tests of actor code, the
+/// first option is picked.
+///
+/// # Panics
+///
+/// When `options` is empty.
+pub fn choose<T>(options: &[T]) -> &T {
+    assert!(!options.is_empty(), "there is no option to choose from");
+    let index = CHOICES
+        .try_with(|choices| choices.lock().unwrap().choose(options.len()))
+        .unwrap_or(0);
+    &options[index]
+}
+
+/// The choices made by a single execution of an action.
+#[derive(Debug, Default)]
+pub struct Choices {
+    /// Options to pick, in order, before falling back to the first option of
+    /// every later choice.
+    script: Vec<usize>,
+    /// The option picked and the number of options of every choice made so
+    /// far.
+    made: Vec<(usize, usize)>,
+}
+
+impl Choices {
+    fn choose(&mut self, option_count: usize) -> usize {
+        let index = self.script.get(self.made.len()).copied().unwrap_or(0);
+        self.made.push((index, option_count));
+        index
+    }
+
+    /// Runs `future`, which picks its options following `self`, and tells the
+    /// choices it made along with its output.
+    pub async fn run<F: Future>(self, future: F) -> (F::Output, Choices) {
+        let choices = Arc::new(Mutex::new(self));
+        let output = CHOICES.scope(choices.clone(), future).await;
+        let choices = mem::take(&mut *choices.lock().unwrap());
+        (output, choices)
+    }
+
+    /// Scripts of the executions picking another option of one of the
+    /// choices made beyond the script, so that running every alternative of
+    /// every run, starting from the empty script, runs every combination of
+    /// options exactly once.
+    pub fn alternatives(&self) -> Vec<Choices> {
+        (self.script.len()..self.made.len())
+            .flat_map(|position| {
+                let (index, option_count) = self.made[position];
+                (index + 1..option_count).map(move |other_index| Choices {
+                    script: self.made[..position]
+                        .iter()
+                        .map(|(index, _)| *index)
+                        .chain([other_index])
+                        .collect(),
+                    made: vec![],
+                })
+            })
+            .collect()
+    }
+}
+
+/// Spawns `future` on a task of its own, which picks its options following
+/// the choices of the calling task, if any.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match CHOICES.try_with(Arc::clone) {
+        Ok(choices) => tokio::spawn(CHOICES.scope(choices, future)),
+        Err(_) => tokio::spawn(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use super::{Choices, choose, spawn};
+
+    /// Picks a letter, then a digit unless the letter is `c`.
+    async fn pick() -> String {
+        let letter = *choose(&['a', 'b', 'c']);
+        if letter == 'c' {
+            return letter.to_string();
+        }
+        format!("{letter}{}", choose(&[1, 2]))
+    }
+
+    #[tokio::test]
+    async fn every_combination_of_options_is_run_once() {
+        let mut outputs = vec![];
+        let mut pending = VecDeque::from([Choices::default()]);
+        while let Some(choices) = pending.pop_front() {
+            let (output, choices) = choices.run(pick()).await;
+            pending.extend(choices.alternatives());
+            outputs.push(output);
+        }
+
+        assert_eq!(outputs.len(), 5);
+        assert_eq!(
+            outputs.into_iter().collect::<HashSet<_>>(),
+            HashSet::from_iter(["a1", "a2", "b1", "b2", "c"].map(String::from))
+        );
+    }
+
+    #[tokio::test]
+    async fn choices_are_kept_when_a_spawned_task_panics() {
+        let (_, choices) = Choices::default()
+            .run(async {
+                spawn(async {
+                    choose(&[1, 2]);
+                    panic!("after the choice");
+                })
+                .await
+            })
+            .await;
+
+        assert_eq!(choices.alternatives().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn first_option_is_picked_outside_of_a_run() {
+        assert_eq!(pick().await, "a1");
+    }
+}
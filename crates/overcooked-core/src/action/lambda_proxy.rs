@@ -3,19 +3,20 @@
 #[macro_export]
 macro_rules! intransitive_action {
     ($actor_type:ident, $method:ident) => {
-        paste::paste! {
+        $crate::paste::paste! {
             pub async fn [<$actor_type:snake _ $method>](
-                actor: ::std::sync::Arc<dyn crate::actor::ActorBase>,
-            ) -> Result<(), Box<dyn ::std::error::Error + Send + Sync>> {
-                Ok(crate::actor::ActorBase::as_any(actor.as_ref())
+                actor: ::std::sync::Arc<dyn $crate::actor::ActorBase>,
+            ) -> Result<$crate::ActionOutput, Box<dyn ::std::error::Error + Send + Sync>> {
+                $crate::actor::ActorBase::as_any(actor.as_ref())
                     .downcast_ref::<$actor_type>()
                     .unwrap()
                     .$method()
                     .await
+                    .map($crate::ActionOutput::new)
                     .map_err(|e| {
                         let err: Box<dyn ::std::error::Error + Send + Sync> = Box::new(e);
                         err
-                    })?)
+                    })
             }
         }
     };
@@ -24,23 +25,57 @@ macro_rules! intransitive_action {
 #[macro_export]
 macro_rules! transitive_action {
     ($action_performer_type:ident, $method:ident, $action_receiver_type:ident) => {
-        paste::paste! {
+        $crate::paste::paste! {
             pub async fn [<$action_performer_type:snake _ $method _ $action_receiver_type:snake>](
-                action_performer: ::std::sync::Arc<dyn crate::actor::ActorBase>,
-                action_receiver: ::std::sync::Arc<dyn crate::actor::ActorBase>,
-            ) -> Result<(), Box<dyn ::std::error::Error + Send + Sync>> {
-                Ok(crate::actor::ActorBase::as_any(action_performer.as_ref())
+                action_performer: ::std::sync::Arc<dyn $crate::actor::ActorBase>,
+                action_receiver: ::std::sync::Arc<dyn $crate::actor::ActorBase>,
+            ) -> Result<$crate::ActionOutput, Box<dyn ::std::error::Error + Send + Sync>> {
+                $crate::actor::ActorBase::as_any(action_performer.as_ref())
                     .downcast_ref::<$action_performer_type>()
                     .unwrap()
                     .$method(
-                        crate::actor::ActorBase::as_any(action_receiver.as_ref())
+                        $crate::actor::ActorBase::as_any(action_receiver.as_ref())
                         .downcast_ref::<$action_receiver_type>()
                         .unwrap())
                     .await
+                    .map($crate::ActionOutput::new)
                     .map_err(|e| {
                         let err: Box<dyn ::std::error::Error + Send + Sync> = Box::new(e);
                         err
-                    })?)
+                    })
+            }
+        }
+    };
+}
+
+/// Same as `transitive_action`, except that the method takes every receiver
+/// at once, e.g. `fn commit(&self, resource_managers: &[&ResourceManager])`.
+#[macro_export]
+macro_rules! multi_receiver_action {
+    ($action_performer_type:ident, $method:ident, $action_receiver_type:ident) => {
+        $crate::paste::paste! {
+            pub async fn [<$action_performer_type:snake _ $method _ $action_receiver_type:snake>](
+                action_performer: ::std::sync::Arc<dyn $crate::actor::ActorBase>,
+                action_receivers: Vec<::std::sync::Arc<dyn $crate::actor::ActorBase>>,
+            ) -> Result<$crate::ActionOutput, Box<dyn ::std::error::Error + Send + Sync>> {
+                let action_receivers: Vec<&$action_receiver_type> = action_receivers
+                    .iter()
+                    .map(|action_receiver| {
+                        $crate::actor::ActorBase::as_any(action_receiver.as_ref())
+                            .downcast_ref::<$action_receiver_type>()
+                            .unwrap()
+                    })
+                    .collect();
+                $crate::actor::ActorBase::as_any(action_performer.as_ref())
+                    .downcast_ref::<$action_performer_type>()
+                    .unwrap()
+                    .$method(&action_receivers)
+                    .await
+                    .map($crate::ActionOutput::new)
+                    .map_err(|e| {
+                        let err: Box<dyn ::std::error::Error + Send + Sync> = Box::new(e);
+                        err
+                    })
             }
         }
     };
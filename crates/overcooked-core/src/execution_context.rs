@@ -1,30 +1,99 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Mutex};
 
 use crate::{
-    action::{ActionResult, ActionTemplate},
+    action::{ActionOutput, ActionResult, ActionTemplate},
     global_state::GlobalState,
+    state_machine_driver::exploration_observer::ExplorationObserver,
     transition::Transition,
 };
 
+/// Captures the states and transitions of a run as they are explored.
+#[derive(Default)]
 pub struct ExecutionContext {
-    global_states: HashSet<GlobalState>,
-    transitions: HashSet<Transition>,
+    global_states: Mutex<HashSet<GlobalState>>,
+    transitions: Mutex<HashSet<Transition>>,
 }
 
 impl ExecutionContext {
     pub fn new() -> Self {
-        Self {
-            global_states: HashSet::new(),
-            transitions: HashSet::new(),
-        }
+        Self::default()
     }
 
     pub fn capture(
-        &mut self,
+        &self,
         from: GlobalState,
         to: GlobalState,
         action_template: ActionTemplate,
         action_result: ActionResult,
+        action_output: Option<ActionOutput>,
     ) {
+        {
+            let mut global_states = self.global_states.lock().unwrap();
+            global_states.insert(from.clone());
+            global_states.insert(to.clone());
+        }
+        self.transitions.lock().unwrap().insert(Transition {
+            from,
+            to,
+            action_template,
+            action_result,
+            action_output,
+        });
+    }
+
+    pub fn global_states(&self) -> HashSet<GlobalState> {
+        self.global_states.lock().unwrap().clone()
+    }
+
+    pub fn transitions(&self) -> HashSet<Transition> {
+        self.transitions.lock().unwrap().clone()
+    }
+}
+
+impl ExplorationObserver for ExecutionContext {
+    fn on_state_discovered(&self, global_state: &GlobalState, _depth: usize) {
+        self.global_states
+            .lock()
+            .unwrap()
+            .insert(global_state.clone());
+    }
+
+    fn on_transition_computed(&self, transition: &Transition) {
+        self.capture(
+            transition.from.clone(),
+            transition.to.clone(),
+            transition.action_template.clone(),
+            transition.action_result.clone(),
+            transition.action_output.clone(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        state_machine_driver::exploration_observer::ExplorationObserver,
+        test_utils::test_graph::{global_state, transition},
+    };
+
+    use super::ExecutionContext;
+
+    #[test]
+    fn observed_states_and_transitions_are_captured() {
+        let execution_context = ExecutionContext::new();
+        execution_context.on_state_discovered(&global_state(0), 0);
+        execution_context.on_transition_computed(&transition(0, 1, "a"));
+        execution_context.on_transition_computed(&transition(0, 1, "a"));
+
+        assert_eq!(
+            execution_context.global_states(),
+            HashSet::from([global_state(0), global_state(1)])
+        );
+        assert_eq!(
+            execution_context.transitions(),
+            HashSet::from([transition(0, 1, "a")])
+        );
     }
 }
@@ -1,9 +1,14 @@
-use std::{collections::BTreeMap, sync::atomic::AtomicU64};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, atomic::AtomicU64},
+};
 
 use crate::actor::{self, local_state::LocalState};
 
 const SEED: AtomicU64 = AtomicU64::new(0);
 
+pub type StatePredicate = Arc<dyn Fn(&GlobalState) -> bool + Send + Sync>;
+
 #[derive(Debug, Clone, Eq)]
 pub struct GlobalState {
     id: u64,
@@ -28,6 +33,35 @@ impl GlobalState {
     pub fn insert_local_state(&mut self, actor_id: actor::Id, local_state: LocalState) {
         self.local_states.insert(actor_id, local_state);
     }
+
+    pub fn local_states(&self) -> impl Iterator<Item = (&actor::Id, &LocalState)> {
+        self.local_states.iter()
+    }
+
+    /// Every global state made of one of the given local states of each
+    /// actor, e.g. to explore every mix of initial configurations at once.
+    pub fn every_combination(choices: BTreeMap<actor::Id, Vec<LocalState>>) -> Vec<GlobalState> {
+        choices
+            .into_iter()
+            .fold(
+                vec![BTreeMap::new()],
+                |combinations, (actor_id, local_states)| {
+                    combinations
+                        .iter()
+                        .flat_map(|combination| {
+                            local_states.iter().map(|local_state| {
+                                let mut combination = combination.clone();
+                                combination.insert(actor_id.clone(), local_state.clone());
+                                combination
+                            })
+                        })
+                        .collect()
+                },
+            )
+            .into_iter()
+            .map(GlobalState::new)
+            .collect()
+    }
 }
 
 impl PartialEq for GlobalState {
@@ -53,7 +87,7 @@ mod tests {
     use crate::{
         actor::{self, local_state::LocalState},
         global_state::GlobalState,
-        test_utils::test_actors::TestActor1State,
+        test_utils::test_actors::{TestActor1State, TestActor2State},
     };
 
     #[test]
@@ -117,6 +151,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn every_combination_of_local_states_is_made() {
+        let local_state_1 = |value| LocalState {
+            actor_state: Arc::new(TestActor1State { value }),
+        };
+        let local_state_2 = |value| LocalState {
+            actor_state: Arc::new(TestActor2State { value }),
+        };
+        let actor_1_id = actor::Id("actor-1".to_string());
+        let actor_2_id = actor::Id("actor-2".to_string());
+
+        let combinations = GlobalState::every_combination(BTreeMap::from([
+            (actor_1_id.clone(), vec![local_state_1(0), local_state_1(1)]),
+            (
+                actor_2_id.clone(),
+                vec![local_state_2(0), local_state_2(1), local_state_2(2)],
+            ),
+        ]));
+
+        assert_eq!(combinations.len(), 6);
+        for actor_1_value in 0..2 {
+            for actor_2_value in 0..3 {
+                assert!(combinations.contains(&GlobalState::new(BTreeMap::from([
+                    (actor_1_id.clone(), local_state_1(actor_1_value)),
+                    (actor_2_id.clone(), local_state_2(actor_2_value)),
+                ]))));
+            }
+        }
+    }
+
     fn hash_it<T: std::hash::Hash>(value: &T) -> u64 {
         let mut hasher = DefaultHasher::new();
         value.hash(&mut hasher);
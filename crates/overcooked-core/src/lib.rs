@@ -4,10 +4,14 @@ mod config;
 mod derives;
 mod execution_context;
 mod global_state;
+mod rng;
 mod state_machine_driver;
 mod transition;
 
+pub use action::{ActionOutput, choose};
 pub(crate) use action::{ActionTemplateExecutor, create_executor};
+#[doc(hidden)]
+pub use paste;
 
 #[cfg(test)]
 mod test_utils;
@@ -1,47 +1,608 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet, VecDeque},
+    time::Instant,
+};
 
-use crate::{global_state::GlobalState, transition::Transition};
+use crate::{
+    action::ActionTemplate,
+    global_state::GlobalState,
+    state_machine_driver::{
+        action_fault::check_action_faults,
+        checkpoint::{Checkpoint, CheckpointError, CheckpointLog},
+        coverage::CoverageTracker,
+        deadlock::{Deadlock, is_stuck},
+        exploration_budget::{BudgetLimit, Termination},
+        exploration_config::ExplorationConfig,
+        exploration_observer::ExplorationObserver,
+        exploration_result::ExplorationResult,
+        invariant::{ViolationPolicy, check_invariants},
+        liveness::check_liveness,
+        partial_order_reduction::PartialOrderReduction,
+        search_strategy::{SearchNode, SearchStrategy},
+        trace::Trace,
+        visited_set::VisitedSet,
+    },
+    transition::Transition,
+};
 
+mod action_fault;
+pub mod checkpoint;
+mod concurrent_visited_set;
+mod coverage;
+mod deadlock;
+mod disk_spill;
+mod exploration_budget;
+mod exploration_config;
+pub mod exploration_observer;
+mod exploration_result;
+mod goal_search;
+mod invariant;
+mod liveness;
+mod parallel_state_machine_driver;
+mod partial_order_reduction;
+mod search_strategy;
 mod simple_transition_computer;
+mod simulator;
+mod symmetry;
+mod trace;
+mod visited_set;
 
 #[mockall::automock]
 #[async_trait::async_trait]
-pub trait TransitionComputer {
+pub trait TransitionComputer: Send + Sync {
     async fn compute(&self, from: GlobalState) -> HashSet<Transition>;
+
+    /// Same as [`TransitionComputer::compute`], along with the failures the
+    /// failed action policy dropped, each leading to the state the actors were
+    /// left in. They are only looked at for coverage.
+    async fn compute_with_dropped(
+        &self,
+        from: GlobalState,
+    ) -> (HashSet<Transition>, Vec<Transition>) {
+        (self.compute(from).await, Vec::new())
+    }
+
+    /// Every action template of the model, which tells checkpoints of
+    /// different models apart.
+    fn action_templates(&self) -> Vec<ActionTemplate>;
 }
 
 pub struct StateMachineDriver {
     transition_computer: Box<dyn TransitionComputer>,
+    config: ExplorationConfig,
+}
+
+/// Bookkeeping of a single run.
+struct Run {
+    initial_states: Vec<GlobalState>,
+    /// Every action template of the model when partial order reduction or
+    /// coverage is configured, which look at them whether they yield
+    /// transitions or not.
+    action_templates: Vec<ActionTemplate>,
+    coverage: Option<CoverageTracker>,
+    search_strategy: Box<dyn SearchStrategy>,
+    /// Depth at which every state visited in the current round was reached,
+    /// keyed by the state it is visited as.
+    visited: Box<dyn VisitedSet>,
+    /// States of the current round left unexplored because of the depth limit.
+    beyond_max_depth: Vec<GlobalState>,
+    started_at: Instant,
+    explored_since_checkpoint: usize,
+    /// Started by the first checkpoint of the run.
+    checkpoint_log: Option<CheckpointLog>,
+    /// Transitions collected since the latest checkpoint.
+    unsaved_transitions: Vec<Transition>,
+    /// Number of depths the observers have been told are completed.
+    completed_depths: usize,
+    deepest_explored: usize,
+    result: ExplorationResult,
 }
 
 impl StateMachineDriver {
-    pub async fn run(&self, initial_state: GlobalState) -> HashSet<Transition> {
-        let mut transitions = HashSet::new();
+    pub fn new(
+        transition_computer: Box<dyn TransitionComputer>,
+        config: ExplorationConfig,
+    ) -> Self {
+        Self {
+            transition_computer,
+            config,
+        }
+    }
+
+    pub async fn run(&self, initial_state: GlobalState) -> ExplorationResult {
+        self.run_from_all([initial_state]).await
+    }
 
-        let mut queue = VecDeque::new();
-        queue.push_back(initial_state);
+    /// Explores the states reachable from any of `initial_states` in a single
+    /// run, so that a state reachable from several of them is explored once.
+    /// Every counterexample starts from one of them, which it tells through
+    /// its `initial_state` method.
+    pub async fn run_from_all(
+        &self,
+        initial_states: impl IntoIterator<Item = GlobalState>,
+    ) -> ExplorationResult {
+        let run = self.start_run(initial_states.into_iter().collect());
+        self.run_rounds(run, false).await
+    }
 
-        let mut visited = HashSet::new();
+    pub async fn resume(
+        &self,
+        initial_state: GlobalState,
+    ) -> Result<ExplorationResult, CheckpointError> {
+        self.resume_from_all([initial_state]).await
+    }
 
-        while let Some(curr) = queue.pop_front() {
-            if visited.contains(&curr) {
-                continue;
+    /// Carries on with the run of the latest checkpoint, which must have been
+    /// taken of a run of the same model from the same `initial_states`.
+    ///
+    /// The visited states are restored from the recorded transitions, along
+    /// with the length of the shortest trace to each of them. Iterative
+    /// deepening starts over from its first round, keeping what was found so
+    /// far.
+    pub async fn resume_from_all(
+        &self,
+        initial_states: impl IntoIterator<Item = GlobalState>,
+    ) -> Result<ExplorationResult, CheckpointError> {
+        let initial_states: Vec<GlobalState> = initial_states.into_iter().collect();
+        let checkpointing = self
+            .config
+            .checkpointing
+            .as_ref()
+            .ok_or(CheckpointError::NotConfigured)?;
+        let checkpoint = Checkpoint::read(
+            checkpointing,
+            &self.transition_computer.action_templates(),
+            &initial_states,
+        )?;
+
+        let mut run = self.start_run(initial_states);
+        self.restore(&mut run, checkpoint);
+        Ok(self.run_rounds(run, true).await)
+    }
+
+    fn start_run(&self, initial_states: Vec<GlobalState>) -> Run {
+        let action_templates: Vec<ActionTemplate> =
+            if self.partial_order_reduction().is_some() || self.config.coverage {
+                self.transition_computer
+                    .action_templates()
+                    .iter()
+                    .flat_map(ActionTemplate::expand)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+        Run {
+            initial_states,
+            coverage: self
+                .config
+                .coverage
+                .then(|| CoverageTracker::new(&action_templates)),
+            action_templates,
+            search_strategy: self.config.search_strategy.create(),
+            visited: self.config.visited_set.create(),
+            beyond_max_depth: Vec::new(),
+            started_at: Instant::now(),
+            explored_since_checkpoint: 0,
+            checkpoint_log: None,
+            unsaved_transitions: Vec::new(),
+            completed_depths: 0,
+            deepest_explored: 0,
+            result: ExplorationResult::default(),
+        }
+    }
+
+    /// Explores round after round until the search strategy is done. A
+    /// `resumed` run carries on with its first round rather than starting it
+    /// over.
+    async fn run_rounds(&self, mut run: Run, mut resumed: bool) -> ExplorationResult {
+        loop {
+            let stopped = if std::mem::take(&mut resumed) {
+                self.explore(&mut run).await
             } else {
-                visited.insert(curr.clone());
+                run.visited.clear();
+                run.beyond_max_depth.clear();
+
+                let initial_nodes: Vec<SearchNode> = run
+                    .initial_states
+                    .iter()
+                    .map(|initial_state| SearchNode {
+                        state: initial_state.clone(),
+                        trace: Trace::default(),
+                    })
+                    .collect();
+                initial_nodes
+                    .into_iter()
+                    .any(|initial_node| self.discover(&mut run, initial_node))
+                    || self.explore(&mut run).await
+            };
+
+            if !stopped {
+                let depth = run.deepest_explored + 1;
+                self.complete_depths_below(&mut run, depth);
+            }
+
+            if stopped || !run.search_strategy.start_next_round() {
+                let initial_states = run.initial_states.clone();
+                let mut result = run.finish();
+                result.liveness_violations = check_liveness(
+                    &self.config.liveness_properties,
+                    &self.config.fairness_constraints,
+                    &initial_states,
+                    &result.transitions,
+                );
+                self.notify(|observer| observer.on_run_finished(&result));
+                return result;
+            }
+        }
+    }
+
+    /// Explores until the search strategy runs out of states. Returns whether
+    /// the run has to stop.
+    async fn explore(&self, run: &mut Run) -> bool {
+        while let Some(node) = run.search_strategy.pop() {
+            if run.revisits_shallower_states()
+                && run
+                    .visited
+                    .depth(&self.canonicalize(&node.state))
+                    .is_some_and(|depth| depth < node.depth())
+            {
+                // A shorter trace to this state has been found since it was pushed.
+                continue;
+            }
+            if run.search_strategy.explores_by_depth() {
+                self.complete_depths_below(run, node.depth());
             }
 
-            let out_going_transitions = self.transition_computer.compute(curr).await;
-            transitions.extend(out_going_transitions.clone());
+            if let Some(limit) = self.config.budget.exhausted_limit(
+                run.visited.len(),
+                run.result.transition_count,
+                run.started_at.elapsed(),
+            ) {
+                run.result.termination = Termination::BudgetExhausted(limit);
+                run.result.frontier.push(node.state);
+                return true;
+            }
+
+            run.deepest_explored = run.deepest_explored.max(node.depth());
+            let out_going_transitions = match &mut run.coverage {
+                Some(coverage) => {
+                    let (out_going_transitions, dropped_failures) = self
+                        .transition_computer
+                        .compute_with_dropped(node.state.clone())
+                        .await;
+                    coverage.record_state(&node.state);
+                    for transition in &out_going_transitions {
+                        coverage.record_execution(transition);
+                        coverage.record_state(&transition.to);
+                    }
+                    for transition in &dropped_failures {
+                        coverage.record_execution(transition);
+                    }
+                    out_going_transitions
+                }
+                None => self.transition_computer.compute(node.state.clone()).await,
+            };
+            for transition in &out_going_transitions {
+                self.notify(|observer| observer.on_transition_computed(transition));
+                if !transition.action_result.is_success() {
+                    self.notify(|observer| observer.on_action_failed(transition));
+                }
+            }
 
-            queue.extend(
-                out_going_transitions
+            if self.check_action_faults(run, &node, &out_going_transitions)
+                || self.check_deadlock(run, &node, &out_going_transitions)
+            {
+                self.collect(run, out_going_transitions);
+                return true;
+            }
+
+            let followed_transitions: Vec<Transition> = match self.partial_order_reduction() {
+                Some(partial_order_reduction) => partial_order_reduction
+                    .ample_set(&run.action_templates, &out_going_transitions, |state| {
+                        run.visited.contains(&self.canonicalize(state))
+                    })
                     .into_iter()
-                    .map(|transition| transition.to)
-                    .collect::<HashSet<_>>(),
-            );
+                    .cloned()
+                    .collect(),
+                None => out_going_transitions.into_iter().collect(),
+            };
+            self.collect(run, followed_transitions.iter().cloned());
+
+            for transition in followed_transitions {
+                let successor = SearchNode {
+                    state: transition.to.clone(),
+                    trace: node.trace.extend(transition),
+                };
+                if self.discover(run, successor) {
+                    return true;
+                }
+            }
+
+            if self.check_spill_error(run) {
+                return true;
+            }
+            self.checkpoint_if_due(run);
         }
 
-        transitions
+        self.check_spill_error(run)
+    }
+
+    /// Ends the run once the visited set or the search strategy failed to move
+    /// data to disk or read it back, since states may have been lost.
+    fn check_spill_error(&self, run: &mut Run) -> bool {
+        let Some(error) = run
+            .search_strategy
+            .take_error()
+            .or_else(|| run.visited.take_error())
+        else {
+            return false;
+        };
+        run.result.spill_error = Some(error);
+        run.result.termination = Termination::SpillFailed;
+        true
+    }
+
+    /// Counts the transitions followed from a state, and keeps them in the
+    /// result unless configured otherwise. Kept transitions are only counted,
+    /// and saved by the next checkpoint, the first time they are followed.
+    fn collect(&self, run: &mut Run, transitions: impl IntoIterator<Item = Transition>) {
+        if self.keeps_transitions() {
+            for transition in transitions {
+                if run.result.transitions.contains(&transition) {
+                    continue;
+                }
+                run.result.transition_count += 1;
+                if self.config.checkpointing.is_some() {
+                    run.unsaved_transitions.push(transition.clone());
+                }
+                run.result.transitions.insert(transition);
+            }
+        } else {
+            run.result.transition_count += transitions.into_iter().count();
+        }
+    }
+
+    /// Liveness properties are checked on the transitions of the result, and
+    /// checkpoints restore visited states from them.
+    fn keeps_transitions(&self) -> bool {
+        !self.config.discard_transitions
+            || !self.config.liveness_properties.is_empty()
+            || self.config.checkpointing.is_some()
+    }
+
+    /// The configured partial order reduction, unless liveness is checked:
+    /// the interleavings it leaves out may be the very runs violating a
+    /// liveness property, or the ones making a cycle unfair.
+    fn partial_order_reduction(&self) -> Option<&PartialOrderReduction> {
+        match (
+            &self.config.partial_order_reduction,
+            self.config.liveness_properties.is_empty(),
+        ) {
+            (Some(partial_order_reduction), true) => Some(partial_order_reduction),
+            _ => None,
+        }
+    }
+
+    /// Hands `node` over to the search strategy unless its state has been
+    /// visited already. Returns whether the run has to stop.
+    fn discover(&self, run: &mut Run, node: SearchNode) -> bool {
+        let canonical_state = self.canonicalize(&node.state);
+        let stop = match run.visited.depth(&canonical_state) {
+            None => {
+                run.visited.insert(&canonical_state, node.depth());
+                self.notify(|observer| observer.on_state_discovered(&node.state, node.depth()));
+                self.check_invariants(run, &node)
+            }
+            Some(depth) if run.revisits_shallower_states() && node.depth() < depth => {
+                run.visited.insert(&canonical_state, node.depth());
+                false
+            }
+            Some(_) => return false,
+        };
+
+        if self.config.budget.is_beyond_max_depth(node.depth()) {
+            run.beyond_max_depth.push(node.state);
+        } else {
+            run.search_strategy.push(node);
+        }
+
+        if stop {
+            run.result.termination = Termination::ViolationFound;
+        }
+        stop
+    }
+
+    /// The state `global_state` is visited as, which is itself unless
+    /// symmetric actors have been declared.
+    fn canonicalize<'a>(&self, global_state: &'a GlobalState) -> Cow<'a, GlobalState> {
+        match &self.config.symmetry {
+            Some(symmetry) => symmetry.canonicalize(global_state),
+            None => Cow::Borrowed(global_state),
+        }
+    }
+
+    /// Classifies the state of `node` as terminal or deadlocked if none of its
+    /// out going transitions makes progress. Returns whether the run has to
+    /// stop.
+    fn check_deadlock(
+        &self,
+        run: &mut Run,
+        node: &SearchNode,
+        out_going_transitions: &HashSet<Transition>,
+    ) -> bool {
+        let Some(deadlock_detection) = &self.config.deadlock_detection else {
+            return false;
+        };
+        if !is_stuck(&node.state, out_going_transitions) {
+            return false;
+        }
+
+        if deadlock_detection.is_terminal(&node.state) {
+            run.result.terminal_states.insert(node.state.clone());
+            return false;
+        }
+
+        if !run
+            .result
+            .deadlocks
+            .iter()
+            .any(|deadlock| deadlock.state == node.state)
+        {
+            run.result.deadlocks.push(Deadlock {
+                state: node.state.clone(),
+                trace: node.trace.to_vec(),
+            });
+        }
+
+        if self.config.violation_policy == ViolationPolicy::StopAtFirstViolation {
+            run.result.termination = Termination::ViolationFound;
+            return true;
+        }
+        false
+    }
+
+    fn notify(&self, event: impl Fn(&dyn ExplorationObserver)) {
+        for observer in &self.config.observers {
+            event(observer.as_ref());
+        }
+    }
+
+    /// Tells the observers about the depths below `depth` they have not been
+    /// told are completed yet.
+    fn complete_depths_below(&self, run: &mut Run, depth: usize) {
+        while run.completed_depths < depth {
+            let completed_depth = run.completed_depths;
+            self.notify(|observer| observer.on_depth_completed(completed_depth));
+            run.completed_depths += 1;
+        }
+    }
+
+    /// Appends a checkpoint to the log of the run once enough states have been
+    /// explored since the previous one. A checkpoint that cannot be written
+    /// does not stop the run, and the next one starts the log over.
+    fn checkpoint_if_due(&self, run: &mut Run) {
+        let Some(checkpointing) = &self.config.checkpointing else {
+            return;
+        };
+        run.explored_since_checkpoint += 1;
+        if run.explored_since_checkpoint < checkpointing.interval {
+            return;
+        }
+
+        run.explored_since_checkpoint = 0;
+        let checkpoint = Checkpoint {
+            initial_states: run.initial_states.clone(),
+            transitions: std::mem::take(&mut run.unsaved_transitions),
+            frontier: run.search_strategy.queued_nodes(),
+            beyond_max_depth: run.beyond_max_depth.clone(),
+            violations: run.result.violations.clone(),
+            deadlocks: run.result.deadlocks.clone(),
+            action_faults: run.result.action_faults.clone(),
+            terminal_states: run.result.terminal_states.iter().cloned().collect(),
+        };
+        let checkpoint_log = run.checkpoint_log.get_or_insert_with(|| {
+            CheckpointLog::new(checkpointing, &self.transition_computer.action_templates())
+        });
+        if let Err(error) = checkpoint_log.append(&checkpoint) {
+            run.result.checkpoint_error = Some(error);
+            run.checkpoint_log = None;
+            run.unsaved_transitions = run.result.transitions.iter().cloned().collect();
+        }
+    }
+
+    /// Brings a new run back to where the run of `checkpoint` was.
+    fn restore(&self, run: &mut Run, checkpoint: Checkpoint) {
+        let mut successors: HashMap<&GlobalState, Vec<&GlobalState>> = HashMap::new();
+        for transition in &checkpoint.transitions {
+            successors
+                .entry(&transition.from)
+                .or_default()
+                .push(&transition.to);
+        }
+
+        // Every visited state is an initial state or the target of a recorded
+        // transition, first reached through a shortest trace.
+        let mut queue: VecDeque<(&GlobalState, usize)> = checkpoint
+            .initial_states
+            .iter()
+            .map(|initial_state| (initial_state, 0))
+            .collect();
+        while let Some((global_state, depth)) = queue.pop_front() {
+            let canonical_state = self.canonicalize(global_state);
+            if run.visited.contains(&canonical_state) {
+                continue;
+            }
+            run.visited.insert(&canonical_state, depth);
+            for successor in successors.get(global_state).into_iter().flatten() {
+                queue.push_back((successor, depth + 1));
+            }
+        }
+
+        for node in checkpoint.frontier {
+            run.search_strategy.push(node);
+        }
+        run.beyond_max_depth = checkpoint.beyond_max_depth;
+        run.result.transition_count = checkpoint.transitions.len();
+        run.unsaved_transitions = checkpoint.transitions.clone();
+        run.result.transitions = checkpoint.transitions.into_iter().collect();
+        run.result.violations = checkpoint.violations;
+        run.result.deadlocks = checkpoint.deadlocks;
+        run.result.action_faults = checkpoint.action_faults;
+        run.result.terminal_states = checkpoint.terminal_states.into_iter().collect();
+    }
+
+    /// Records a fault for every transition out of the state of `node` whose
+    /// action panicked or timed out. Returns whether the run has to stop.
+    fn check_action_faults(
+        &self,
+        run: &mut Run,
+        node: &SearchNode,
+        out_going_transitions: &HashSet<Transition>,
+    ) -> bool {
+        let stop = check_action_faults(
+            out_going_transitions,
+            &node.trace,
+            &mut run.result.action_faults,
+        ) && self.config.violation_policy == ViolationPolicy::StopAtFirstViolation;
+        if stop {
+            run.result.termination = Termination::ViolationFound;
+        }
+        stop
+    }
+
+    /// Records a violation for every invariant that does not hold in the
+    /// state of `node` and tells whether the run should stop.
+    fn check_invariants(&self, run: &mut Run, node: &SearchNode) -> bool {
+        check_invariants(
+            &self.config.invariants,
+            &node.state,
+            &node.trace,
+            &mut run.result.violations,
+        ) && self.config.violation_policy == ViolationPolicy::StopAtFirstViolation
+    }
+}
+
+impl Run {
+    /// Whether visited states are explored again once reached through a
+    /// shorter trace, which needs the visited set to keep their depths.
+    fn revisits_shallower_states(&self) -> bool {
+        self.search_strategy.revisits_shallower_states() && self.visited.keeps_depths()
+    }
+
+    fn finish(mut self) -> ExplorationResult {
+        if self.result.termination == Termination::Completed && !self.beyond_max_depth.is_empty() {
+            self.result.termination = Termination::BudgetExhausted(BudgetLimit::Depth);
+        }
+
+        let mut frontier = self.beyond_max_depth;
+        frontier.extend(std::iter::from_fn(|| self.search_strategy.pop()).map(|node| node.state));
+        self.result.frontier.extend(frontier);
+        self.result.fingerprint_collision_probability = self.visited.collision_probability();
+        self.result.bitstate_report = self.visited.bitstate_report();
+        self.result.coverage = self.coverage.map(|coverage| coverage.report());
+        self.result.visited_states = self.visited.into_states();
+        self.result
     }
 }
 
@@ -49,18 +610,44 @@ impl StateMachineDriver {
 mod tests {
     use std::{
         collections::{BTreeMap, HashSet},
+        env,
         error::Error,
-        sync::{Arc, LazyLock},
+        fs,
+        sync::{Arc, LazyLock, Mutex},
+        time::Duration,
     };
 
     use mockall::predicate::eq;
+    use test_case::test_case;
 
     use crate::{
-        action::{ActionResult, ActionTemplate, ActionType},
+        action::{ActionOutput, ActionResult, ActionTemplate, ActionType},
         actor::{self, ActorBase, actor_state::ActorState, local_state::LocalState},
+        execution_context::ExecutionContext,
         global_state::GlobalState,
-        state_machine_driver::{MockTransitionComputer, StateMachineDriver},
-        test_utils::test_actors::TestActor1State,
+        state_machine_driver::{
+            MockTransitionComputer, StateMachineDriver,
+            checkpoint::{CheckpointError, Checkpointing},
+            deadlock::DeadlockDetection,
+            disk_spill::{DiskSpill, SpillCodecs},
+            exploration_budget::{BudgetLimit, ExplorationBudget, Termination},
+            exploration_config::ExplorationConfig,
+            exploration_observer::ExplorationObserver,
+            exploration_result::ExplorationResult,
+            invariant::{Invariant, ViolationPolicy},
+            liveness::{LivenessProperty, Ltl},
+            partial_order_reduction::PartialOrderReduction,
+            search_strategy::SearchStrategyType,
+            symmetry::Symmetry,
+            visited_set::VisitedSetType,
+        },
+        test_utils::{
+            test_actors::TestActor1State,
+            test_graph::{
+                self, GraphTransitionComputer, TestActor1StateCodec, TestOutputCodec,
+                failed_transition, panicked_transition, value_of,
+            },
+        },
         transition::Transition,
     };
 
@@ -185,12 +772,11 @@ mod tests {
             .once()
             .return_once(|_| HashSet::from([transition_4_d_4]));
 
-        let state_machine_driver = StateMachineDriver {
-            transition_computer: Box::new(transition_computer),
-        };
+        let state_machine_driver =
+            StateMachineDriver::new(Box::new(transition_computer), ExplorationConfig::default());
 
         assert_eq!(
-            state_machine_driver.run(global_state_0).await,
+            state_machine_driver.run(global_state_0).await.transitions,
             HashSet::from([
                 transition_0_a_1_clone,
                 transition_0_b_2_clone,
@@ -204,6 +790,1045 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn invariant_violation_is_reported_with_shortest_trace() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(4)],
+                violation_policy: ViolationPolicy::StopAtFirstViolation,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(result.violations.len(), 1);
+        let violation = &result.violations[0];
+        assert_eq!(violation.invariant, "value is below 4");
+        assert_eq!(violation.state, test_graph::global_state(4));
+        assert_eq!(violation.trace.len(), 2);
+        assert_eq!(violation.trace[0], test_graph::transition(0, 2, ACTION_B));
+        assert_eq!(violation.trace[1].from, test_graph::global_state(2));
+        assert_eq!(violation.trace[1].to, test_graph::global_state(4));
+    }
+
+    #[tokio::test]
+    async fn run_continues_after_violation_when_configured() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(3)],
+                violation_policy: ViolationPolicy::Continue,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(
+            result
+                .violations
+                .iter()
+                .map(|violation| (value_of(&violation.state), violation.trace.len()))
+                .collect::<HashSet<_>>(),
+            HashSet::from([(3, 2), (4, 2)])
+        );
+    }
+
+    #[tokio::test]
+    async fn violation_in_initial_state_has_empty_trace() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(MockTransitionComputer::new()),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(0)],
+                violation_policy: ViolationPolicy::StopAtFirstViolation,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert!(result.transitions.is_empty());
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].trace.is_empty());
+    }
+
+    #[tokio::test]
+    async fn several_initial_states_are_explored_in_one_run() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(3)],
+                violation_policy: ViolationPolicy::Continue,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver
+            .run_from_all([test_graph::global_state(1), test_graph::global_state(2)])
+            .await;
+
+        assert_eq!(
+            result.transitions,
+            HashSet::from_iter(
+                graph()
+                    .into_iter()
+                    .filter(|transition| value_of(&transition.from) != 0)
+            )
+        );
+        assert_eq!(
+            result
+                .violations
+                .iter()
+                .map(|violation| (
+                    value_of(&violation.state),
+                    value_of(violation.initial_state())
+                ))
+                .collect::<HashSet<_>>(),
+            HashSet::from([(3, 1), (4, 2)])
+        );
+    }
+
+    #[test_case(SearchStrategyType::BreadthFirst)]
+    #[test_case(SearchStrategyType::DepthFirst)]
+    #[test_case(SearchStrategyType::IterativeDeepening)]
+    #[test_case(SearchStrategyType::RandomPriority { seed: 0 })]
+    #[test_case(SearchStrategyType::RandomPriority { seed: 1 })]
+    #[tokio::test]
+    async fn every_search_strategy_explores_the_same_state_machine(
+        search_strategy: SearchStrategyType,
+    ) {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                search_strategy,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(result.transition_count, graph().len());
+        assert_eq!(
+            result.visited_states,
+            HashSet::from_iter((0..=4).map(test_graph::global_state))
+        );
+        assert!(result.violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn transitions_followed_again_do_not_count_towards_the_budget() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                search_strategy: SearchStrategyType::IterativeDeepening,
+                budget: budget(None, None, Some(graph().len()), None),
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(result.transition_count, graph().len());
+    }
+
+    #[test_case(SearchStrategyType::BreadthFirst)]
+    #[test_case(SearchStrategyType::IterativeDeepening)]
+    #[tokio::test]
+    async fn search_strategy_finds_shortest_trace(search_strategy: SearchStrategyType) {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(3), invariant_value_below(4)],
+                violation_policy: ViolationPolicy::Continue,
+                search_strategy,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(
+            result
+                .violations
+                .iter()
+                .map(|violation| (
+                    violation.invariant.clone(),
+                    value_of(&violation.state),
+                    violation.trace.len()
+                ))
+                .collect::<HashSet<_>>(),
+            HashSet::from([
+                ("value is below 3".to_string(), 3, 2),
+                ("value is below 3".to_string(), 4, 2),
+                ("value is below 4".to_string(), 4, 2),
+            ])
+        );
+        assert_eq!(result.violations.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn depth_first_search_reports_a_trace_to_the_violation() {
+        let state_machine_driver = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(4)],
+                search_strategy: SearchStrategyType::DepthFirst,
+                ..ExplorationConfig::default()
+            },
+        );
+
+        let result = state_machine_driver.run(test_graph::global_state(0)).await;
+
+        assert_eq!(result.violations.len(), 1);
+        let trace = &result.violations[0].trace;
+        assert_eq!(trace.first().unwrap().from, test_graph::global_state(0));
+        assert_eq!(trace.last().unwrap().to, test_graph::global_state(4));
+        assert!(trace.windows(2).all(|pair| pair[0].to == pair[1].from));
+    }
+
+    #[tokio::test]
+    async fn completed_run_has_no_frontier() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig::default(),
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::Completed);
+        assert!(result.frontier.is_empty());
+    }
+
+    #[rustfmt::skip]
+    //          budget,                                                  limit,                  visited, frontier
+    #[test_case(budget(Some(3), None,    None,    None),                   BudgetLimit::Depth,       4,       3)]
+    #[test_case(budget(None,    Some(5), None,    None),                   BudgetLimit::States,      5,       4)]
+    #[test_case(budget(None,    None,    Some(3), None),                   BudgetLimit::Transitions, 4,       3)]
+    #[test_case(budget(None,    None,    None,    Some(Duration::ZERO)),   BudgetLimit::Time,        1,       0)]
+    #[test_case(budget(Some(3), Some(2), None,    None),                   BudgetLimit::States,      2,       1)]
+    #[tokio::test]
+    async fn budget_bounds_the_exploration(
+        budget: ExplorationBudget,
+        limit: BudgetLimit,
+        visited_state_count: usize,
+        frontier: u8,
+    ) {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(chain())),
+            ExplorationConfig {
+                budget,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::BudgetExhausted(limit));
+        assert_eq!(
+            result.visited_states,
+            HashSet::from_iter((0..visited_state_count as u8).map(test_graph::global_state))
+        );
+        assert_eq!(
+            result.transitions,
+            HashSet::from_iter((0..frontier).map(|value| test_graph::transition(
+                value,
+                value + 1,
+                ACTION_A
+            )))
+        );
+        assert_eq!(result.frontier, vec![test_graph::global_state(frontier)]);
+    }
+
+    #[tokio::test]
+    async fn violation_ends_the_run_with_its_termination() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(chain())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(2)],
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.frontier, vec![test_graph::global_state(2)]);
+    }
+
+    /// ```text
+    ///          ┌──────┐
+    ///          │ GS_0 │
+    ///          └──┬───┘
+    ///     ┌───────┼────────┐
+    ///    a│      b│       c│
+    /// ┌───▼──┐ ┌──▼───┐ ┌──▼───┐
+    /// │ GS_1 │ │ GS_2 │ │ GS_3 │
+    /// └┬──▲──┘ └┬──▲──┘ └──────┘
+    ///  └──┘d    └──┘e (fails)
+    /// ```
+    fn graph_with_stuck_states() -> Vec<Transition> {
+        vec![
+            test_graph::transition(0, 1, ACTION_A),
+            test_graph::transition(0, 2, ACTION_B),
+            test_graph::transition(0, 3, ACTION_C),
+            test_graph::transition(1, 1, ACTION_D),
+            failed_transition(2, 2, ACTION_E),
+        ]
+    }
+
+    #[tokio::test]
+    async fn deadlocks_are_told_apart_from_terminal_states() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph_with_stuck_states())),
+            ExplorationConfig {
+                violation_policy: ViolationPolicy::Continue,
+                deadlock_detection: Some(DeadlockDetection {
+                    is_terminal: Some(Arc::new(|global_state| value_of(global_state) == 1)),
+                }),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::Completed);
+        assert_eq!(
+            result.terminal_states,
+            HashSet::from([test_graph::global_state(1)])
+        );
+        assert_eq!(
+            result
+                .deadlocks
+                .iter()
+                .map(|deadlock| (value_of(&deadlock.state), deadlock.trace.clone()))
+                .collect::<HashSet<_>>(),
+            HashSet::from([
+                (2, vec![test_graph::transition(0, 2, ACTION_B)]),
+                (3, vec![test_graph::transition(0, 3, ACTION_C)]),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn first_deadlock_stops_the_run() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph_with_stuck_states())),
+            ExplorationConfig {
+                deadlock_detection: Some(DeadlockDetection::default()),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.deadlocks.len(), 1);
+        assert!(result.terminal_states.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deadlocks_are_not_looked_for_unless_enabled() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph_with_stuck_states())),
+            ExplorationConfig::default(),
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::Completed);
+        assert!(result.deadlocks.is_empty());
+    }
+
+    #[test_case(ViolationPolicy::StopAtFirstViolation, Termination::ViolationFound ; "stopping at the first one")]
+    #[test_case(ViolationPolicy::Continue, Termination::Completed ; "carrying on")]
+    #[tokio::test]
+    async fn action_faults_are_reported_with_their_trace(
+        violation_policy: ViolationPolicy,
+        expected_termination: Termination,
+    ) {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new([
+                test_graph::transition(0, 1, ACTION_A),
+                test_graph::transition(1, 2, ACTION_A),
+                panicked_transition(1, ACTION_B),
+            ])),
+            ExplorationConfig {
+                violation_policy,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, expected_termination);
+        assert_eq!(result.action_faults.len(), 1);
+        let action_fault = &result.action_faults[0];
+        assert_eq!(action_fault.transition, panicked_transition(1, ACTION_B));
+        assert_eq!(
+            action_fault.trace,
+            vec![test_graph::transition(0, 1, ACTION_A)]
+        );
+        assert!(result.transitions.contains(&action_fault.transition));
+    }
+
+    #[tokio::test]
+    async fn liveness_violation_is_reported_as_lasso() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                liveness_properties: vec![LivenessProperty {
+                    name: "eventually 4".to_string(),
+                    formula: Ltl::eventually(Ltl::state("4", |global_state| {
+                        value_of(global_state) == 4
+                    })),
+                }],
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.liveness_violations.len(), 1);
+        let violation = &result.liveness_violations[0];
+        assert_eq!(violation.property, "eventually 4");
+        assert_eq!(
+            violation.prefix,
+            vec![test_graph::transition(0, 2, ACTION_B)]
+        );
+        assert_eq!(
+            violation.cycle,
+            vec![test_graph::transition(2, 2, ACTION_B)]
+        );
+    }
+
+    static OTHER_ACTOR_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_2".to_string()));
+
+    fn two_actor_state(value: u8, other_value: u8) -> GlobalState {
+        let mut global_state = global_state(value);
+        global_state.insert_local_state(
+            OTHER_ACTOR_ID.clone(),
+            LocalState {
+                actor_state: actor_state(other_value),
+            },
+        );
+        global_state
+    }
+
+    /// Two counters counting up to 2 independently of each other.
+    fn independent_counters() -> Vec<Transition> {
+        let mut transitions = vec![];
+        for value in 0..=2 {
+            for other_value in 0..=2 {
+                if value < 2 {
+                    transitions.push(transition(
+                        two_actor_state(value, other_value),
+                        two_actor_state(value + 1, other_value),
+                        ACTION_A,
+                    ));
+                }
+                if other_value < 2 {
+                    let mut other_transition = transition(
+                        two_actor_state(value, other_value),
+                        two_actor_state(value, other_value + 1),
+                        ACTION_B,
+                    );
+                    other_transition.action_template.performer_id = OTHER_ACTOR_ID.clone();
+                    transitions.push(other_transition);
+                }
+            }
+        }
+        transitions
+    }
+
+    #[test_case(None,                                                              9; "without reduction")]
+    #[test_case(Some(PartialOrderReduction::new(HashSet::new())),                  5; "with reduction")]
+    #[test_case(Some(PartialOrderReduction::new(HashSet::from([
+        ACTOR_ID.clone(),
+        OTHER_ACTOR_ID.clone(),
+    ]))),                                                                           9; "with visible actors")]
+    #[tokio::test]
+    async fn partial_order_reduction_skips_interleavings(
+        partial_order_reduction: Option<PartialOrderReduction>,
+        visited_state_count: usize,
+    ) {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(independent_counters())),
+            ExplorationConfig {
+                invariants: vec![Invariant {
+                    name: "not both done".to_string(),
+                    predicate: Arc::new(|global_state| *global_state != two_actor_state(2, 2)),
+                }],
+                partial_order_reduction,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.violations[0].trace.len(), 4);
+        assert_eq!(result.visited_states.len(), visited_state_count);
+    }
+
+    #[tokio::test]
+    async fn partial_order_reduction_keeps_interleavings_of_visible_actors() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(independent_counters())),
+            ExplorationConfig {
+                invariants: vec![Invariant {
+                    name: "second counter never first".to_string(),
+                    predicate: Arc::new(|global_state| *global_state != two_actor_state(0, 1)),
+                }],
+                partial_order_reduction: Some(PartialOrderReduction::new(HashSet::from([
+                    ACTOR_ID.clone(),
+                    OTHER_ACTOR_ID.clone(),
+                ]))),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.violations[0].state, two_actor_state(0, 1));
+    }
+
+    /// `a` counts up on actor 1 and `b` on actor 2, while `d`, from actor 2 to
+    /// actor 1, is only enabled once `b` has been taken before `a`.
+    fn guarded_counters() -> Vec<Transition> {
+        let other_transition = |from: GlobalState, to: GlobalState, label: &str, action_type| {
+            let mut transition = transition(from, to, label);
+            transition.action_template =
+                ActionTemplate::new(OTHER_ACTOR_ID.clone(), label.to_string(), action_type);
+            transition
+        };
+        vec![
+            transition(two_actor_state(0, 0), two_actor_state(1, 0), ACTION_A),
+            transition(two_actor_state(0, 1), two_actor_state(1, 1), ACTION_A),
+            other_transition(
+                two_actor_state(0, 0),
+                two_actor_state(0, 1),
+                ACTION_B,
+                test_graph::action(ACTION_B).action_type,
+            ),
+            other_transition(
+                two_actor_state(1, 0),
+                two_actor_state(1, 1),
+                ACTION_B,
+                test_graph::action(ACTION_B).action_type,
+            ),
+            other_transition(
+                two_actor_state(0, 1),
+                two_actor_state(0, 2),
+                ACTION_D,
+                ActionType::Transitive {
+                    receiver_id: ACTOR_ID.clone(),
+                    action: Arc::new(|_, _| Box::pin(async { Ok(ActionOutput::new(())) })),
+                },
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn coverage_counts_the_actions_partial_order_reduction_does_not_follow() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(independent_counters())),
+            ExplorationConfig {
+                partial_order_reduction: Some(PartialOrderReduction::new(HashSet::new())),
+                coverage: true,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        // Both actions are taken from the first two states, then only the one
+        // of the counter left behind.
+        assert_eq!(result.transition_count, 4);
+        let coverage = result.coverage.unwrap();
+        assert_eq!(
+            coverage
+                .actions
+                .iter()
+                .map(|coverage| coverage.successes)
+                .sum::<usize>(),
+            6
+        );
+        assert_eq!(coverage.never_executed().count(), 0);
+        assert_eq!(
+            coverage.local_state_counts,
+            BTreeMap::from([(ACTOR_ID.clone(), 3), (OTHER_ACTOR_ID.clone(), 3)])
+        );
+    }
+
+    #[tokio::test]
+    async fn partial_order_reduction_keeps_actions_enabling_dependent_ones() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(guarded_counters())),
+            ExplorationConfig {
+                invariants: vec![Invariant {
+                    name: "d is never taken".to_string(),
+                    predicate: Arc::new(|global_state| *global_state != two_actor_state(0, 2)),
+                }],
+                partial_order_reduction: Some(PartialOrderReduction::new(HashSet::from([
+                    OTHER_ACTOR_ID.clone(),
+                ]))),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.violations[0].state, two_actor_state(0, 2));
+        assert_eq!(result.violations[0].trace.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn liveness_is_checked_on_every_interleaving_despite_partial_order_reduction() {
+        // Both counters count up to 1, after which the second one spins.
+        let other_transition = |from, to, label| {
+            let mut transition = transition(from, to, label);
+            transition.action_template.performer_id = OTHER_ACTOR_ID.clone();
+            transition
+        };
+        let transitions = vec![
+            transition(two_actor_state(0, 0), two_actor_state(1, 0), ACTION_A),
+            transition(two_actor_state(0, 1), two_actor_state(1, 1), ACTION_A),
+            other_transition(two_actor_state(0, 0), two_actor_state(0, 1), ACTION_B),
+            other_transition(two_actor_state(1, 0), two_actor_state(1, 1), ACTION_B),
+            other_transition(two_actor_state(0, 1), two_actor_state(0, 1), ACTION_C),
+            other_transition(two_actor_state(1, 1), two_actor_state(1, 1), ACTION_C),
+        ];
+
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(transitions)),
+            ExplorationConfig {
+                liveness_properties: vec![LivenessProperty {
+                    name: "eventually counted".to_string(),
+                    formula: Ltl::eventually(Ltl::state("counted", |global_state| {
+                        *global_state == two_actor_state(1, 0)
+                            || *global_state == two_actor_state(1, 1)
+                    })),
+                }],
+                partial_order_reduction: Some(PartialOrderReduction::new(HashSet::new())),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        assert_eq!(result.visited_states.len(), 4);
+        assert_eq!(result.liveness_violations.len(), 1);
+        assert_eq!(
+            result.liveness_violations[0].cycle,
+            vec![other_transition(
+                two_actor_state(0, 1),
+                two_actor_state(0, 1),
+                ACTION_C
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn symmetric_actors_are_visited_once_per_permutation() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(independent_counters())),
+            ExplorationConfig {
+                invariants: vec![Invariant {
+                    name: "not both done".to_string(),
+                    predicate: Arc::new(|global_state| *global_state != two_actor_state(2, 2)),
+                }],
+                violation_policy: ViolationPolicy::Continue,
+                symmetry: Some(Symmetry {
+                    groups: vec![vec![ACTOR_ID.clone(), OTHER_ACTOR_ID.clone()]],
+                }),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(two_actor_state(0, 0))
+        .await;
+
+        // {0, 0}, {0, 1}, {0, 2}, {1, 1}, {1, 2} and {2, 2}
+        assert_eq!(result.visited_states.len(), 6);
+        assert_eq!(result.violations.len(), 1);
+        let trace = &result.violations[0].trace;
+        assert_eq!(trace.len(), 4);
+        assert_eq!(trace.first().unwrap().from, two_actor_state(0, 0));
+        assert_eq!(trace.last().unwrap().to, two_actor_state(2, 2));
+        assert!(trace.windows(2).all(|pair| pair[0].to == pair[1].from));
+        assert!(
+            trace
+                .iter()
+                .all(|transition| independent_counters().contains(transition))
+        );
+    }
+
+    #[test_case(VisitedSetType::Fingerprint64)]
+    #[test_case(VisitedSetType::Fingerprint128)]
+    #[tokio::test]
+    async fn fingerprints_explore_the_same_state_machine(visited_set: VisitedSetType) {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(4)],
+                violation_policy: ViolationPolicy::Continue,
+                visited_set,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.visited_states.is_empty());
+        assert!(result.fingerprint_collision_probability.unwrap() < 1e-15);
+    }
+
+    #[tokio::test]
+    async fn discarded_transitions_are_only_counted() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(chain())),
+            ExplorationConfig {
+                visited_set: VisitedSetType::Fingerprint64,
+                discard_transitions: true,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::Completed);
+        assert_eq!(result.transition_count, chain().len());
+        assert!(result.transitions.is_empty());
+        assert!(result.visited_states.is_empty());
+        assert!(result.frontier.is_empty());
+    }
+
+    #[tokio::test]
+    async fn transitions_are_kept_for_liveness_even_if_discarded() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                discard_transitions: true,
+                liveness_properties: vec![LivenessProperty {
+                    name: "eventually 4".to_string(),
+                    formula: Ltl::eventually(Ltl::state("4", |global_state| {
+                        value_of(global_state) == 4
+                    })),
+                }],
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(result.transition_count, graph().len());
+        assert_eq!(result.liveness_violations.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn observers_are_told_about_the_progress_of_the_run() {
+        let recorder = Arc::new(EventRecorder::default());
+        let execution_context = Arc::new(ExecutionContext::new());
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph_with_stuck_states())),
+            ExplorationConfig {
+                observers: vec![recorder.clone(), execution_context.clone()],
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.starts_with("discovered"))
+                .count(),
+            result.visited_states.len()
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.starts_with("computed"))
+                .count(),
+            result.transitions.len()
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.starts_with("failed"))
+                .collect::<Vec<_>>(),
+            ["failed action_e"]
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.starts_with("depth"))
+                .collect::<Vec<_>>(),
+            ["depth 0", "depth 1"]
+        );
+        assert_eq!(events.first().unwrap(), "discovered 0");
+        assert_eq!(events.last().unwrap(), "finished");
+        assert_eq!(execution_context.transitions(), result.transitions);
+        assert_eq!(execution_context.global_states(), result.visited_states);
+    }
+
+    #[tokio::test]
+    async fn interrupted_runs_are_resumed_from_their_latest_checkpoint() {
+        let checkpointing = Checkpointing {
+            path: env::temp_dir().join(format!("resume-{}.checkpoint", std::process::id())),
+            interval: 1,
+            codec: Arc::new(TestActor1StateCodec),
+            action_output_codec: Arc::new(TestOutputCodec),
+        };
+        let config = ExplorationConfig {
+            invariants: vec![invariant_value_below(4)],
+            violation_policy: ViolationPolicy::Continue,
+            checkpointing: Some(checkpointing.clone()),
+            ..ExplorationConfig::default()
+        };
+        let interrupted = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                budget: budget(None, Some(3), None, None),
+                ..config.clone()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        let resumed = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            config.clone(),
+        )
+        .resume(test_graph::global_state(0))
+        .await;
+        let other_model =
+            StateMachineDriver::new(Box::new(GraphTransitionComputer::new(chain())), config)
+                .resume(test_graph::global_state(0))
+                .await;
+        fs::remove_file(checkpointing.path).unwrap();
+
+        assert_eq!(
+            interrupted.termination,
+            Termination::BudgetExhausted(BudgetLimit::States)
+        );
+        assert!(interrupted.transitions.len() < graph().len());
+        let resumed = resumed.unwrap();
+        assert_eq!(resumed.termination, Termination::Completed);
+        assert_eq!(resumed.transitions, HashSet::from_iter(graph()));
+        assert_eq!(resumed.violations.len(), 1);
+        assert_eq!(resumed.violations[0].trace.len(), 2);
+        assert!(resumed.checkpoint_error.is_none());
+        assert!(matches!(
+            other_model,
+            Err(CheckpointError::ModelMismatch(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn runs_cannot_be_resumed_without_checkpointing() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig::default(),
+        )
+        .resume(test_graph::global_state(0))
+        .await;
+
+        assert!(matches!(result, Err(CheckpointError::NotConfigured)));
+    }
+
+    #[tokio::test]
+    async fn spilling_to_disk_explores_the_same_state_machine() {
+        let directory = env::temp_dir().join(format!("disk-spill-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let disk_spill = DiskSpill {
+            directory: directory.clone(),
+            memory_threshold: 0,
+        };
+
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                invariants: vec![invariant_value_below(4)],
+                violation_policy: ViolationPolicy::Continue,
+                search_strategy: SearchStrategyType::DiskBreadthFirst {
+                    disk_spill: disk_spill.clone(),
+                    codecs: SpillCodecs {
+                        codec: Arc::new(TestActor1StateCodec),
+                        action_output_codec: Arc::new(TestOutputCodec),
+                    },
+                },
+                visited_set: VisitedSetType::Disk { disk_spill },
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].trace.len(), 2);
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+        fs::remove_dir(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn spilling_errors_end_the_run() {
+        let file = env::temp_dir().join(format!("not-a-directory-run-{}", std::process::id()));
+        fs::write(&file, []).unwrap();
+
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                visited_set: VisitedSetType::Disk {
+                    disk_spill: DiskSpill {
+                        directory: file.clone(),
+                        memory_threshold: 0,
+                    },
+                },
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::SpillFailed);
+        assert!(result.spill_error.is_some());
+        fs::remove_file(file).unwrap();
+    }
+
+    #[test_case(SearchStrategyType::BreadthFirst)]
+    #[test_case(SearchStrategyType::DepthFirst)]
+    #[test_case(SearchStrategyType::IterativeDeepening)]
+    #[tokio::test]
+    async fn bitstate_hashing_explores_small_models_entirely(search_strategy: SearchStrategyType) {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            ExplorationConfig {
+                search_strategy,
+                visited_set: VisitedSetType::Bitstate {
+                    memory_bytes: 1 << 16,
+                    hash_functions: 3,
+                },
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+        let report = result.bitstate_report.unwrap();
+        assert!(report.fill_ratio > 0.0 && report.fill_ratio < 0.001);
+        assert!(report.estimated_coverage > 0.999);
+    }
+
+    #[tokio::test]
+    async fn bitstate_hashing_misses_states_once_crowded() {
+        let result = StateMachineDriver::new(
+            Box::new(GraphTransitionComputer::new(chain())),
+            ExplorationConfig {
+                visited_set: VisitedSetType::Bitstate {
+                    memory_bytes: 8,
+                    hash_functions: 2,
+                },
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(test_graph::global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::Completed);
+        assert!(result.transitions.len() < chain().len());
+        assert!(result.bitstate_report.unwrap().estimated_coverage < 1.0);
+    }
+
+    /// Records the events it is told about as strings.
+    #[derive(Default)]
+    struct EventRecorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl EventRecorder {
+        fn record(&self, event: String) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    impl ExplorationObserver for EventRecorder {
+        fn on_state_discovered(&self, global_state: &GlobalState, _depth: usize) {
+            self.record(format!("discovered {}", value_of(global_state)));
+        }
+
+        fn on_transition_computed(&self, transition: &Transition) {
+            self.record(format!("computed {}", transition.action_template.label));
+        }
+
+        fn on_action_failed(&self, transition: &Transition) {
+            self.record(format!("failed {}", transition.action_template.label));
+        }
+
+        fn on_depth_completed(&self, depth: usize) {
+            self.record(format!("depth {depth}"));
+        }
+
+        fn on_run_finished(&self, _result: &ExplorationResult) {
+            self.record("finished".to_string());
+        }
+    }
+
+    fn budget(
+        max_depth: Option<usize>,
+        max_states: Option<usize>,
+        max_transitions: Option<usize>,
+        time_limit: Option<Duration>,
+    ) -> ExplorationBudget {
+        ExplorationBudget {
+            max_depth,
+            max_states,
+            max_transitions,
+            time_limit,
+        }
+    }
+
+    /// A counter that can be increased up to [`u8::MAX`].
+    fn chain() -> Vec<Transition> {
+        (0..u8::MAX)
+            .map(|value| test_graph::transition(value, value + 1, ACTION_A))
+            .collect()
+    }
+
+    /// Same state machine as the one in [`works`].
+    fn graph() -> Vec<Transition> {
+        vec![
+            test_graph::transition(0, 1, ACTION_A),
+            test_graph::transition(0, 2, ACTION_B),
+            test_graph::transition(1, 3, ACTION_C),
+            test_graph::transition(2, 2, ACTION_B),
+            test_graph::transition(2, 4, ACTION_C),
+            test_graph::transition(2, 4, ACTION_E),
+            test_graph::transition(3, 4, ACTION_D),
+            test_graph::transition(4, 4, ACTION_D),
+        ]
+    }
+
+    fn invariant_value_below(bound: u8) -> Invariant {
+        Invariant {
+            name: format!("value is below {bound}"),
+            predicate: Arc::new(move |global_state| value_of(global_state) < bound),
+        }
+    }
+
     // TODO: group these utils under the module of GlobalState
     fn global_state(actor_state_value: u8) -> GlobalState {
         GlobalState::new(BTreeMap::from([(
@@ -219,7 +1844,8 @@ mod tests {
             from,
             to,
             action_template: action(action_label),
-            action_result: ActionResult(None),
+            action_result: ActionResult::Succeeded,
+            action_output: Some(ActionOutput::new(())),
         }
     }
 
@@ -228,19 +1854,19 @@ mod tests {
     }
 
     fn action(label: &str) -> ActionTemplate {
-        ActionTemplate {
-            performer_id: ACTOR_ID.clone(),
-            label: label.to_string(),
-            action_type: ActionType::Intransitive(Arc::new(|actor| {
+        ActionTemplate::new(
+            ACTOR_ID.clone(),
+            label.to_string(),
+            ActionType::Intransitive(Arc::new(|actor| {
                 Box::pin(proxy_for_intransitive_action(actor))
             })),
-        }
+        )
     }
 
     /// todo: move this to a util module
     async fn proxy_for_intransitive_action(
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 }
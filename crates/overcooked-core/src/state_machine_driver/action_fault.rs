@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use crate::{
+    global_state::GlobalState, state_machine_driver::trace::Trace, transition::Transition,
+};
+
+/// A transition whose action panicked or timed out, together with the
+/// shortest sequence of transitions leading to the state it was taken from.
+#[derive(Debug, Clone)]
+pub struct ActionFault {
+    pub transition: Transition,
+    pub trace: Vec<Transition>,
+}
+
+impl ActionFault {
+    /// The state `trace` starts from.
+    pub fn initial_state(&self) -> &GlobalState {
+        self.trace
+            .first()
+            .map_or(&self.transition.from, |transition| &transition.from)
+    }
+}
+
+/// Records a fault for every transition of `out_going_transitions` whose
+/// action panicked or timed out, `trace` leading to the state they are taken
+/// from, and tells whether there was any. Faults that have been recorded
+/// before are not recorded again.
+pub fn check_action_faults(
+    out_going_transitions: &HashSet<Transition>,
+    trace: &Trace,
+    faults: &mut Vec<ActionFault>,
+) -> bool {
+    let mut faulted = false;
+    for transition in out_going_transitions {
+        if !transition.action_result.is_fault() {
+            continue;
+        }
+
+        faulted = true;
+        if !faults.iter().any(|fault| fault.transition == *transition) {
+            faults.push(ActionFault {
+                transition: transition.clone(),
+                trace: trace.to_vec(),
+            });
+        }
+    }
+
+    faulted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        state_machine_driver::trace::Trace,
+        test_utils::test_graph::{failed_transition, panicked_transition, transition},
+    };
+
+    use super::check_action_faults;
+
+    #[test]
+    fn faults_are_recorded_once_per_transition() {
+        let trace = Trace::default().extend(transition(0, 1, "a"));
+        let mut faults = vec![];
+
+        assert!(!check_action_faults(
+            &HashSet::from([transition(1, 2, "a"), failed_transition(1, 1, "b")]),
+            &trace,
+            &mut faults
+        ));
+        assert!(check_action_faults(
+            &HashSet::from([transition(1, 2, "a"), panicked_transition(1, "c")]),
+            &trace,
+            &mut faults
+        ));
+        assert!(check_action_faults(
+            &HashSet::from([panicked_transition(1, "c")]),
+            &trace,
+            &mut faults
+        ));
+
+        assert_eq!(faults.len(), 1);
+        assert_eq!(faults[0].transition, panicked_transition(1, "c"));
+        assert_eq!(faults[0].trace, trace.to_vec());
+        assert_eq!(faults[0].initial_state(), &trace.to_vec()[0].from);
+    }
+}
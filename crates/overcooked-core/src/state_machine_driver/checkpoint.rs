@@ -0,0 +1,910 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    action::{ActionOutput, ActionResult, ActionTemplate},
+    actor::{self, local_state::LocalState},
+    global_state::GlobalState,
+    state_machine_driver::{
+        action_fault::ActionFault,
+        checkpoint::encoding::{Reader, Writer},
+        deadlock::Deadlock,
+        invariant::InvariantViolation,
+        search_strategy::SearchNode,
+        trace::Trace,
+    },
+    transition::Transition,
+};
+
+mod encoding;
+
+const MAGIC: &[u8] = b"overcooked checkpoint 6\n";
+
+/// Enables periodic checkpoints of a run, which
+/// [`StateMachineDriver::resume`](super::StateMachineDriver::resume) picks up
+/// after an interruption.
+#[derive(Clone)]
+pub struct Checkpointing {
+    /// File the checkpoints of a run are appended to.
+    pub path: PathBuf,
+    /// Number of states explored between two checkpoints.
+    pub interval: usize,
+    pub codec: Arc<dyn LocalStateCodec>,
+    pub action_output_codec: Arc<dyn ActionOutputCodec>,
+}
+
+/// Turns the local states of actors into bytes and back, as actor states are
+/// only known to the driver through [`ActorState`](crate::actor::actor_state::ActorState).
+pub trait LocalStateCodec: Send + Sync {
+    fn encode(&self, actor_id: &actor::Id, local_state: &LocalState) -> Vec<u8>;
+
+    /// Returns `None` if `bytes` is not the encoding of a local state of
+    /// `actor_id`.
+    fn decode(&self, actor_id: &actor::Id, bytes: &[u8]) -> Option<LocalState>;
+}
+
+/// Turns what actions return and the errors they fail with into bytes and
+/// back, so that properties of a resumed run can still downcast them.
+pub trait ActionOutputCodec: Send + Sync {
+    fn encode_output(
+        &self,
+        action_template: &ActionTemplate,
+        action_output: &ActionOutput,
+    ) -> Vec<u8>;
+
+    /// Returns `None` if `bytes` is not the encoding of an output of
+    /// `action_template`.
+    fn decode_output(&self, action_template: &ActionTemplate, bytes: &[u8])
+    -> Option<ActionOutput>;
+
+    fn encode_error(
+        &self,
+        action_template: &ActionTemplate,
+        error: &(dyn Error + Send + Sync + 'static),
+    ) -> Vec<u8>;
+
+    /// Returns `None` if `bytes` is not the encoding of an error of
+    /// `action_template`.
+    fn decode_error(
+        &self,
+        action_template: &ActionTemplate,
+        bytes: &[u8],
+    ) -> Option<Arc<dyn Error + Send + Sync>>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("checkpointing is not configured")]
+    NotConfigured,
+    #[error("failed to access the checkpoint: {0}")]
+    Io(#[from] io::Error),
+    #[error("the checkpoint is corrupted")]
+    Corrupted,
+    #[error("the checkpoint was taken of another model: {0}")]
+    ModelMismatch(String),
+}
+
+/// What a run has found so far and what it has left to explore.
+pub struct Checkpoint {
+    pub initial_states: Vec<GlobalState>,
+    pub transitions: Vec<Transition>,
+    /// Nodes waiting to be explored, in the order they are to be pushed
+    /// again.
+    pub frontier: Vec<SearchNode>,
+    pub beyond_max_depth: Vec<GlobalState>,
+    pub violations: Vec<InvariantViolation>,
+    pub deadlocks: Vec<Deadlock>,
+    pub action_faults: Vec<ActionFault>,
+    pub terminal_states: Vec<GlobalState>,
+}
+
+/// What tells an action template apart from the others of a model.
+type ActionKey = (String, String, Vec<String>);
+
+fn action_key(action_template: &ActionTemplate) -> ActionKey {
+    (
+        action_template.performer_id.0.clone(),
+        action_template.label.clone(),
+        action_template
+            .receiver_ids()
+            .into_iter()
+            .map(|receiver_id| receiver_id.0.clone())
+            .collect(),
+    )
+}
+
+impl Checkpoint {
+    /// Writes `self` to the file of `checkpointing` as the first checkpoint of
+    /// a new [`CheckpointLog`], replacing the previous checkpoint only once
+    /// the new one is complete.
+    pub fn write(
+        &self,
+        checkpointing: &Checkpointing,
+        action_templates: &[ActionTemplate],
+    ) -> Result<(), CheckpointError> {
+        CheckpointLog::new(checkpointing, action_templates).append(self)
+    }
+
+    /// Reads the latest checkpoint of the log written to the file of
+    /// `checkpointing`, provided it was taken of a run of the model made of
+    /// `action_templates` from `initial_states`, in any order. A checkpoint
+    /// that was being appended when the run was interrupted is left out.
+    pub fn read(
+        checkpointing: &Checkpointing,
+        action_templates: &[ActionTemplate],
+        initial_states: &[GlobalState],
+    ) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(&checkpointing.path)?;
+        let mut reader = Reader::new(
+            bytes
+                .strip_prefix(MAGIC)
+                .ok_or(CheckpointError::Corrupted)?,
+        );
+
+        let action_keys = reader.vec(|reader| {
+            Ok((
+                reader.string()?,
+                reader.string()?,
+                reader.vec(Reader::string)?,
+            ))
+        })?;
+        let templates_by_key: HashMap<ActionKey, &ActionTemplate> = action_templates
+            .iter()
+            .map(|action_template| (action_key(action_template), action_template))
+            .collect();
+        if action_keys.len() != templates_by_key.len()
+            || action_keys
+                .iter()
+                .any(|key| !templates_by_key.contains_key(key))
+        {
+            return Err(CheckpointError::ModelMismatch(format!(
+                "the actions were {:?}",
+                action_keys
+                    .iter()
+                    .map(|(_, label, _)| label)
+                    .collect::<Vec<_>>()
+            )));
+        }
+
+        let mut decoder = Decoder {
+            codec: checkpointing.codec.as_ref(),
+            output_codec: checkpointing.action_output_codec.as_ref(),
+            action_templates: action_keys
+                .iter()
+                .map(|key| templates_by_key[key])
+                .collect(),
+            actor_ids: Vec::new(),
+            states: Vec::new(),
+            transitions: Vec::new(),
+        };
+        let mut checkpoint: Option<Checkpoint> = None;
+        while let Some(frame) = reader.frame() {
+            let mut frame = Reader::new(frame);
+            decoder.decode_frame(&mut frame, checkpoint.get_or_insert_with(Checkpoint::empty))?;
+            if !frame.is_empty() {
+                return Err(CheckpointError::Corrupted);
+            }
+        }
+        let checkpoint = checkpoint.ok_or(CheckpointError::Corrupted)?;
+
+        let expected_actor_ids: BTreeSet<&actor::Id> = initial_states
+            .iter()
+            .flat_map(|initial_state| initial_state.local_states().map(|(id, _)| id))
+            .collect();
+        if expected_actor_ids != decoder.actor_ids.iter().collect() {
+            return Err(CheckpointError::ModelMismatch(format!(
+                "the actors were {:?}",
+                decoder.actor_ids
+            )));
+        }
+        if checkpoint.initial_states.iter().collect::<HashSet<_>>()
+            != initial_states.iter().collect()
+        {
+            return Err(CheckpointError::ModelMismatch(format!(
+                "the initial states were {:?}",
+                checkpoint.initial_states
+            )));
+        }
+        Ok(checkpoint)
+    }
+
+    fn empty() -> Self {
+        Self {
+            initial_states: Vec::new(),
+            transitions: Vec::new(),
+            frontier: Vec::new(),
+            beyond_max_depth: Vec::new(),
+            violations: Vec::new(),
+            deadlocks: Vec::new(),
+            action_faults: Vec::new(),
+            terminal_states: Vec::new(),
+        }
+    }
+}
+
+/// The checkpoints of a single run, appended to the file of its
+/// [`Checkpointing`] one frame at a time.
+///
+/// The states and transitions checkpoints refer to are written once, when
+/// first met, and so are the violations, deadlocks, faults and terminal
+/// states, so that a checkpoint costs what was found since the previous one
+/// plus the frontier. The first checkpoint replaces whatever the file held.
+pub struct CheckpointLog {
+    path: PathBuf,
+    action_keys: BTreeSet<ActionKey>,
+    encoder: Encoder,
+    created: bool,
+    violations: usize,
+    deadlocks: usize,
+    action_faults: usize,
+    terminal_states: HashSet<u32>,
+}
+
+impl CheckpointLog {
+    pub fn new(checkpointing: &Checkpointing, action_templates: &[ActionTemplate]) -> Self {
+        let action_keys: BTreeSet<ActionKey> = action_templates.iter().map(action_key).collect();
+        Self {
+            path: checkpointing.path.clone(),
+            encoder: Encoder {
+                codec: checkpointing.codec.clone(),
+                output_codec: checkpointing.action_output_codec.clone(),
+                action_indices: action_keys
+                    .iter()
+                    .enumerate()
+                    .map(|(index, key)| (key.clone(), index as u32))
+                    .collect(),
+                actor_ids: Table::default(),
+                states: Table::default(),
+                transitions: Table::default(),
+            },
+            action_keys,
+            created: false,
+            violations: 0,
+            deadlocks: 0,
+            action_faults: 0,
+            terminal_states: HashSet::new(),
+        }
+    }
+
+    /// Appends what `checkpoint` holds beyond the checkpoints appended so far,
+    /// except for its transitions, which are all appended: they are to be the
+    /// ones found since the previous checkpoint.
+    ///
+    /// The log is left unusable if this fails, as its file may no longer hold
+    /// what has been appended to it.
+    pub fn append(&mut self, checkpoint: &Checkpoint) -> Result<(), CheckpointError> {
+        let mut snapshot = Writer::default();
+        snapshot.count(checkpoint.initial_states.len());
+        for initial_state in &checkpoint.initial_states {
+            let state = self.encoder.state(initial_state);
+            snapshot.u32(state);
+        }
+
+        snapshot.count(checkpoint.transitions.len());
+        for transition in &checkpoint.transitions {
+            let transition = self.encoder.transition(transition)?;
+            snapshot.u32(transition);
+        }
+
+        let violations = &checkpoint.violations[self.violations..];
+        snapshot.count(violations.len());
+        for violation in violations {
+            snapshot.string(&violation.invariant);
+            let state = self.encoder.state(&violation.state);
+            snapshot.u32(state);
+            self.encoder.trace(&violation.trace, &mut snapshot)?;
+        }
+
+        let deadlocks = &checkpoint.deadlocks[self.deadlocks..];
+        snapshot.count(deadlocks.len());
+        for deadlock in deadlocks {
+            let state = self.encoder.state(&deadlock.state);
+            snapshot.u32(state);
+            self.encoder.trace(&deadlock.trace, &mut snapshot)?;
+        }
+
+        let action_faults = &checkpoint.action_faults[self.action_faults..];
+        snapshot.count(action_faults.len());
+        for action_fault in action_faults {
+            let transition = self.encoder.transition(&action_fault.transition)?;
+            snapshot.u32(transition);
+            self.encoder.trace(&action_fault.trace, &mut snapshot)?;
+        }
+
+        let mut terminal_states = Vec::new();
+        for global_state in &checkpoint.terminal_states {
+            let state = self.encoder.state(global_state);
+            if !self.terminal_states.contains(&state) {
+                terminal_states.push(state);
+            }
+        }
+        snapshot.count(terminal_states.len());
+        for state in &terminal_states {
+            snapshot.u32(*state);
+        }
+
+        snapshot.count(checkpoint.frontier.len());
+        for node in &checkpoint.frontier {
+            let state = self.encoder.state(&node.state);
+            snapshot.u32(state);
+            self.encoder.trace(&node.trace.to_vec(), &mut snapshot)?;
+        }
+
+        snapshot.count(checkpoint.beyond_max_depth.len());
+        for global_state in &checkpoint.beyond_max_depth {
+            let state = self.encoder.state(global_state);
+            snapshot.u32(state);
+        }
+
+        let mut frame = self.encoder.take_new_entries();
+        frame.bytes.extend(snapshot.bytes);
+        let mut bytes = Writer::default();
+        if !self.created {
+            bytes.bytes.extend_from_slice(MAGIC);
+            bytes.count(self.action_keys.len());
+            for (performer_id, label, receiver_ids) in &self.action_keys {
+                bytes.string(performer_id);
+                bytes.string(label);
+                bytes.count(receiver_ids.len());
+                for receiver_id in receiver_ids {
+                    bytes.string(receiver_id);
+                }
+            }
+        }
+        bytes.bytes(&frame.bytes);
+
+        if self.created {
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&self.path)?
+                .write_all(&bytes.bytes)?;
+        } else {
+            let temporary_path = self.path.with_extension("partial");
+            fs::write(&temporary_path, bytes.bytes)?;
+            fs::rename(temporary_path, &self.path)?;
+            self.created = true;
+        }
+
+        self.violations = checkpoint.violations.len();
+        self.deadlocks = checkpoint.deadlocks.len();
+        self.action_faults = checkpoint.action_faults.len();
+        self.terminal_states.extend(terminal_states);
+        Ok(())
+    }
+}
+
+/// Writes the actors, states and transitions of the checkpoints of a log
+/// once, so that the rest of them refers to them by index.
+struct Encoder {
+    codec: Arc<dyn LocalStateCodec>,
+    output_codec: Arc<dyn ActionOutputCodec>,
+    action_indices: HashMap<ActionKey, u32>,
+    actor_ids: Table<actor::Id>,
+    states: Table<GlobalState>,
+    transitions: Table<Transition>,
+}
+
+/// Encoded values, each written once. `bytes` holds the ones that have not
+/// been taken into a frame yet.
+struct Table<T> {
+    indices: HashMap<T, u32>,
+    bytes: Vec<u8>,
+    new_entries: usize,
+}
+
+impl Encoder {
+    /// The entries of the tables added since they were last taken, which the
+    /// snapshot of a frame may refer to.
+    fn take_new_entries(&mut self) -> Writer {
+        let mut frame = Writer::default();
+        for (count, bytes) in [
+            self.actor_ids.take(),
+            self.states.take(),
+            self.transitions.take(),
+        ] {
+            frame.count(count);
+            frame.bytes.extend(bytes);
+        }
+        frame
+    }
+
+    fn trace(
+        &mut self,
+        transitions: &[Transition],
+        body: &mut Writer,
+    ) -> Result<(), CheckpointError> {
+        body.count(transitions.len());
+        for transition in transitions {
+            let transition = self.transition(transition)?;
+            body.u32(transition);
+        }
+        Ok(())
+    }
+
+    fn state(&mut self, global_state: &GlobalState) -> u32 {
+        if let Some(index) = self.states.indices.get(global_state) {
+            return *index;
+        }
+
+        let mut writer = Writer::default();
+        let local_states: Vec<_> = global_state.local_states().collect();
+        writer.count(local_states.len());
+        for (actor_id, local_state) in local_states {
+            let actor_index = match self.actor_ids.indices.get(actor_id) {
+                Some(actor_index) => *actor_index,
+                None => {
+                    let mut actor = Writer::default();
+                    actor.string(&actor_id.0);
+                    self.actor_ids.insert(actor_id, actor)
+                }
+            };
+            writer.u32(actor_index);
+            writer.bytes(&self.codec.encode(actor_id, local_state));
+        }
+        self.states.insert(global_state, writer)
+    }
+
+    fn transition(&mut self, transition: &Transition) -> Result<u32, CheckpointError> {
+        if let Some(index) = self.transitions.indices.get(transition) {
+            return Ok(*index);
+        }
+
+        let action_index = *self
+            .action_indices
+            .get(&action_key(&transition.action_template))
+            .ok_or_else(|| {
+                CheckpointError::ModelMismatch(format!(
+                    "action {} is not registered",
+                    transition.action_template.label
+                ))
+            })?;
+        let mut writer = Writer::default();
+        let from = self.state(&transition.from);
+        writer.u32(from);
+        let to = self.state(&transition.to);
+        writer.u32(to);
+        writer.u32(action_index);
+        match &transition.action_result {
+            ActionResult::Succeeded => writer.u32(0),
+            ActionResult::Failed(error) => {
+                writer.u32(1);
+                writer.bytes(
+                    &self
+                        .output_codec
+                        .encode_error(&transition.action_template, error.as_ref()),
+                );
+            }
+            ActionResult::Panicked(message) => {
+                writer.u32(2);
+                writer.string(message);
+            }
+            ActionResult::TimedOut(timeout) => {
+                writer.u32(3);
+                writer.u32(u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX));
+            }
+        }
+        writer.option_bytes(
+            transition
+                .action_output
+                .as_ref()
+                .map(|action_output| {
+                    self.output_codec
+                        .encode_output(&transition.action_template, action_output)
+                })
+                .as_deref(),
+        );
+        Ok(self.transitions.insert(transition, writer))
+    }
+}
+
+/// Reads back the frames of a log, building up the tables they add to.
+struct Decoder<'a> {
+    codec: &'a dyn LocalStateCodec,
+    output_codec: &'a dyn ActionOutputCodec,
+    action_templates: Vec<&'a ActionTemplate>,
+    actor_ids: Vec<actor::Id>,
+    states: Vec<GlobalState>,
+    transitions: Vec<Transition>,
+}
+
+impl Decoder<'_> {
+    /// Adds what `frame` holds to the tables and to `checkpoint`, whose
+    /// frontier it replaces.
+    fn decode_frame(
+        &mut self,
+        frame: &mut Reader,
+        checkpoint: &mut Checkpoint,
+    ) -> Result<(), CheckpointError> {
+        let actor_ids = frame.vec(|reader| reader.string().map(actor::Id))?;
+        self.actor_ids.extend(actor_ids);
+        for _ in 0..frame.count()? {
+            let global_state = self.decode_state(frame)?;
+            self.states.push(global_state);
+        }
+        for _ in 0..frame.count()? {
+            let transition = self.decode_transition(frame)?;
+            self.transitions.push(transition);
+        }
+
+        checkpoint.initial_states = frame.vec(|reader| self.state(reader))?;
+        let transitions = self.trace(frame)?;
+        checkpoint.transitions.extend(transitions);
+        let violations = frame.vec(|reader| {
+            Ok(InvariantViolation {
+                invariant: reader.string()?,
+                state: self.state(reader)?,
+                trace: self.trace(reader)?,
+            })
+        })?;
+        checkpoint.violations.extend(violations);
+        let deadlocks = frame.vec(|reader| {
+            Ok(Deadlock {
+                state: self.state(reader)?,
+                trace: self.trace(reader)?,
+            })
+        })?;
+        checkpoint.deadlocks.extend(deadlocks);
+        let action_faults = frame.vec(|reader| {
+            Ok(ActionFault {
+                transition: self.transition(reader)?,
+                trace: self.trace(reader)?,
+            })
+        })?;
+        checkpoint.action_faults.extend(action_faults);
+        let terminal_states = frame.vec(|reader| self.state(reader))?;
+        checkpoint.terminal_states.extend(terminal_states);
+        checkpoint.frontier = frame.vec(|reader| {
+            let state = self.state(reader)?;
+            let trace = self
+                .trace(reader)?
+                .into_iter()
+                .fold(Trace::default(), |trace, transition| {
+                    trace.extend(transition)
+                });
+            Ok(SearchNode { state, trace })
+        })?;
+        checkpoint.beyond_max_depth = frame.vec(|reader| self.state(reader))?;
+        Ok(())
+    }
+
+    fn decode_state(&self, reader: &mut Reader) -> Result<GlobalState, CheckpointError> {
+        let local_states = reader.vec(|reader| {
+            let actor_id = self
+                .actor_ids
+                .get(reader.count()?)
+                .ok_or(CheckpointError::Corrupted)?;
+            let local_state = self
+                .codec
+                .decode(actor_id, reader.bytes()?)
+                .ok_or(CheckpointError::Corrupted)?;
+            Ok((actor_id.clone(), local_state))
+        })?;
+        Ok(GlobalState::new(local_states.into_iter().collect()))
+    }
+
+    fn decode_transition(&self, reader: &mut Reader) -> Result<Transition, CheckpointError> {
+        let from = self.state(reader)?;
+        let to = self.state(reader)?;
+        let action_template = *self
+            .action_templates
+            .get(reader.count()?)
+            .ok_or(CheckpointError::Corrupted)?;
+        let action_result = match reader.u32()? {
+            0 => ActionResult::Succeeded,
+            1 => ActionResult::Failed(
+                self.output_codec
+                    .decode_error(action_template, reader.bytes()?)
+                    .ok_or(CheckpointError::Corrupted)?,
+            ),
+            2 => ActionResult::Panicked(reader.string()?),
+            3 => ActionResult::TimedOut(Duration::from_millis(reader.u32()?.into())),
+            _ => return Err(CheckpointError::Corrupted),
+        };
+        let action_output = match reader.option_bytes()? {
+            Some(bytes) => Some(
+                self.output_codec
+                    .decode_output(action_template, bytes)
+                    .ok_or(CheckpointError::Corrupted)?,
+            ),
+            None => None,
+        };
+        Ok(Transition {
+            from,
+            to,
+            action_template: action_template.clone(),
+            action_result,
+            action_output,
+        })
+    }
+
+    fn state(&self, reader: &mut Reader) -> Result<GlobalState, CheckpointError> {
+        self.states
+            .get(reader.count()?)
+            .cloned()
+            .ok_or(CheckpointError::Corrupted)
+    }
+
+    fn transition(&self, reader: &mut Reader) -> Result<Transition, CheckpointError> {
+        self.transitions
+            .get(reader.count()?)
+            .cloned()
+            .ok_or(CheckpointError::Corrupted)
+    }
+
+    fn trace(&self, reader: &mut Reader) -> Result<Vec<Transition>, CheckpointError> {
+        reader.vec(|reader| self.transition(reader))
+    }
+}
+
+impl<T: Clone + Eq + std::hash::Hash> Table<T> {
+    fn insert(&mut self, value: &T, writer: Writer) -> u32 {
+        let index = self.indices.len() as u32;
+        self.indices.insert(value.clone(), index);
+        self.bytes.extend(writer.bytes);
+        self.new_entries += 1;
+        index
+    }
+
+    /// The number of entries that have not been taken yet, and their bytes.
+    fn take(&mut self) -> (usize, Vec<u8>) {
+        (
+            std::mem::take(&mut self.new_entries),
+            std::mem::take(&mut self.bytes),
+        )
+    }
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            indices: HashMap::new(),
+            bytes: Vec::new(),
+            new_entries: 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for Checkpointing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Checkpointing")
+            .field("path", &self.path)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, sync::Arc};
+
+    use crate::{
+        action::ActionResult,
+        state_machine_driver::{
+            action_fault::ActionFault, deadlock::Deadlock, invariant::InvariantViolation,
+            search_strategy::SearchNode, trace::Trace,
+        },
+        test_utils::{
+            test_actors::TestActor1Error,
+            test_graph::{
+                TestActor1StateCodec, TestOutputCodec, action, failed_transition, global_state,
+                panicked_transition, transition, value_of,
+            },
+        },
+    };
+
+    use super::{Checkpoint, CheckpointError, CheckpointLog, Checkpointing};
+
+    fn checkpointing(name: &str) -> Checkpointing {
+        Checkpointing {
+            path: env::temp_dir().join(format!("{name}-{}.checkpoint", std::process::id())),
+            interval: 1,
+            codec: Arc::new(TestActor1StateCodec),
+            action_output_codec: Arc::new(TestOutputCodec),
+        }
+    }
+
+    fn checkpoint() -> Checkpoint {
+        Checkpoint {
+            initial_states: vec![global_state(0)],
+            transitions: vec![
+                transition(0, 1, "a"),
+                failed_transition(1, 1, "b"),
+                transition(1, 2, "a"),
+            ],
+            frontier: vec![SearchNode {
+                state: global_state(2),
+                trace: Trace::default()
+                    .extend(transition(0, 1, "a"))
+                    .extend(transition(1, 2, "a")),
+            }],
+            beyond_max_depth: vec![global_state(1)],
+            violations: vec![InvariantViolation {
+                invariant: "below 2".to_string(),
+                state: global_state(2),
+                trace: vec![transition(0, 1, "a"), transition(1, 2, "a")],
+            }],
+            deadlocks: vec![Deadlock {
+                state: global_state(1),
+                trace: vec![transition(0, 1, "a")],
+            }],
+            action_faults: vec![ActionFault {
+                transition: panicked_transition(2, "b"),
+                trace: vec![transition(0, 1, "a"), transition(1, 2, "a")],
+            }],
+            terminal_states: vec![global_state(2)],
+        }
+    }
+
+    /// What follows [`checkpoint`] once the state it left to explore has been.
+    fn next_checkpoint() -> Checkpoint {
+        Checkpoint {
+            transitions: vec![transition(2, 3, "a")],
+            frontier: vec![SearchNode {
+                state: global_state(3),
+                trace: Trace::default()
+                    .extend(transition(0, 1, "a"))
+                    .extend(transition(1, 2, "a"))
+                    .extend(transition(2, 3, "a")),
+            }],
+            beyond_max_depth: vec![],
+            ..checkpoint()
+        }
+    }
+
+    fn remove(path: PathBuf) {
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn checkpoints_are_read_as_they_were_written() {
+        let checkpointing = checkpointing("round-trip");
+        let action_templates = [action("a"), action("b")];
+        checkpoint()
+            .write(&checkpointing, &action_templates)
+            .unwrap();
+
+        let restored = Checkpoint::read(&checkpointing, &action_templates, &[global_state(0)]);
+        remove(checkpointing.path);
+        let restored = restored.unwrap();
+        assert_eq!(restored.transitions, checkpoint().transitions);
+        assert_eq!(
+            restored.transitions[1].action_result.to_string(),
+            "TestActor1Error"
+        );
+        let ActionResult::Failed(error) = &restored.transitions[1].action_result else {
+            panic!("the failure is not restored as such");
+        };
+        assert!(error.downcast_ref::<TestActor1Error>().is_some());
+        assert_eq!(
+            restored.transitions[0]
+                .action_output
+                .as_ref()
+                .and_then(|action_output| action_output.downcast_ref::<()>()),
+            Some(&())
+        );
+        assert_eq!(restored.transitions[1].action_output, None);
+        assert_eq!(value_of(&restored.frontier[0].state), 2);
+        assert_eq!(
+            restored.frontier[0].trace.to_vec(),
+            checkpoint().frontier[0].trace.to_vec()
+        );
+        assert_eq!(restored.beyond_max_depth, vec![global_state(1)]);
+        assert_eq!(restored.violations[0].invariant, "below 2");
+        assert_eq!(restored.violations[0].trace.len(), 2);
+        assert_eq!(restored.deadlocks[0].state, global_state(1));
+        assert_eq!(
+            restored.action_faults[0]
+                .transition
+                .action_result
+                .to_string(),
+            "panicked: boom"
+        );
+        assert_eq!(restored.action_faults[0].trace.len(), 2);
+        assert_eq!(restored.terminal_states, vec![global_state(2)]);
+    }
+
+    #[test]
+    fn later_checkpoints_only_append_what_is_new() {
+        let checkpointing = checkpointing("append");
+        let action_templates = [action("a"), action("b")];
+        let mut log = CheckpointLog::new(&checkpointing, &action_templates);
+        log.append(&checkpoint()).unwrap();
+        let first_len = fs::metadata(&checkpointing.path).unwrap().len();
+        log.append(&next_checkpoint()).unwrap();
+        let appended_len = fs::metadata(&checkpointing.path).unwrap().len() - first_len;
+
+        let restored = Checkpoint::read(&checkpointing, &action_templates, &[global_state(0)]);
+        remove(checkpointing.path);
+        let restored = restored.unwrap();
+        assert!(appended_len < first_len / 2);
+        assert_eq!(
+            restored.transitions,
+            [checkpoint().transitions, next_checkpoint().transitions].concat()
+        );
+        assert_eq!(value_of(&restored.frontier[0].state), 3);
+        assert_eq!(restored.frontier[0].trace.len(), 3);
+        assert!(restored.beyond_max_depth.is_empty());
+        assert_eq!(restored.violations.len(), 1);
+        assert_eq!(restored.deadlocks.len(), 1);
+        assert_eq!(restored.action_faults.len(), 1);
+        assert_eq!(restored.terminal_states, vec![global_state(2)]);
+    }
+
+    #[test]
+    fn checkpoint_cut_short_is_left_out() {
+        let checkpointing = checkpointing("cut-short");
+        let action_templates = [action("a"), action("b")];
+        let mut log = CheckpointLog::new(&checkpointing, &action_templates);
+        log.append(&checkpoint()).unwrap();
+        log.append(&next_checkpoint()).unwrap();
+        let bytes = fs::read(&checkpointing.path).unwrap();
+        fs::write(&checkpointing.path, &bytes[..bytes.len() - 1]).unwrap();
+
+        let restored = Checkpoint::read(&checkpointing, &action_templates, &[global_state(0)]);
+        remove(checkpointing.path);
+        let restored = restored.unwrap();
+        assert_eq!(restored.transitions, checkpoint().transitions);
+        assert_eq!(value_of(&restored.frontier[0].state), 2);
+        assert_eq!(restored.beyond_max_depth, vec![global_state(1)]);
+    }
+
+    #[test]
+    fn checkpoints_of_other_models_are_rejected() {
+        let checkpointing = checkpointing("mismatch");
+        checkpoint()
+            .write(&checkpointing, &[action("a"), action("b")])
+            .unwrap();
+
+        let other_actions = Checkpoint::read(&checkpointing, &[action("a")], &[global_state(0)]);
+        let other_initial_state = Checkpoint::read(
+            &checkpointing,
+            &[action("a"), action("b")],
+            &[global_state(0), global_state(3)],
+        );
+        remove(checkpointing.path);
+        assert!(matches!(
+            other_actions,
+            Err(CheckpointError::ModelMismatch(_))
+        ));
+        assert!(matches!(
+            other_initial_state,
+            Err(CheckpointError::ModelMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn unregistered_actions_cannot_be_checkpointed() {
+        let checkpointing = checkpointing("unregistered");
+
+        assert!(matches!(
+            checkpoint().write(&checkpointing, &[action("a")]),
+            Err(CheckpointError::ModelMismatch(_))
+        ));
+        assert!(!checkpointing.path.exists());
+    }
+
+    #[test]
+    fn corrupted_checkpoints_are_rejected() {
+        let checkpointing = checkpointing("corrupted");
+        checkpoint()
+            .write(&checkpointing, &[action("a"), action("b")])
+            .unwrap();
+        let bytes = fs::read(&checkpointing.path).unwrap();
+        fs::write(&checkpointing.path, &bytes[..bytes.len() - 1]).unwrap();
+
+        let restored = Checkpoint::read(
+            &checkpointing,
+            &[action("a"), action("b")],
+            &[global_state(0)],
+        );
+        remove(checkpointing.path);
+        assert!(matches!(restored, Err(CheckpointError::Corrupted)));
+    }
+}
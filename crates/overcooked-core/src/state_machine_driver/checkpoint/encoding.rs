@@ -0,0 +1,149 @@
+use crate::state_machine_driver::checkpoint::CheckpointError;
+
+/// Appends little endian values to a byte buffer.
+#[derive(Default)]
+pub struct Writer {
+    pub bytes: Vec<u8>,
+}
+
+/// Reads back the values appended by a [`Writer`].
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Writer {
+    pub fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn count(&mut self, count: usize) {
+        self.u32(count as u32);
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.count(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn string(&mut self, string: &str) {
+        self.bytes(string.as_bytes());
+    }
+
+    pub fn option_bytes(&mut self, bytes: Option<&[u8]>) {
+        match bytes {
+            Some(bytes) => {
+                self.u32(1);
+                self.bytes(bytes);
+            }
+            None => self.u32(0),
+        }
+    }
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn u32(&mut self) -> Result<u32, CheckpointError> {
+        let value = self.take(4)?;
+        Ok(u32::from_le_bytes(value.try_into().unwrap()))
+    }
+
+    pub fn count(&mut self) -> Result<usize, CheckpointError> {
+        self.u32().map(|count| count as usize)
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], CheckpointError> {
+        let len = self.count()?;
+        self.take(len)
+    }
+
+    pub fn string(&mut self) -> Result<String, CheckpointError> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| CheckpointError::Corrupted)
+    }
+
+    pub fn option_bytes(&mut self) -> Result<Option<&'a [u8]>, CheckpointError> {
+        match self.u32()? {
+            0 => Ok(None),
+            1 => self.bytes().map(Some),
+            _ => Err(CheckpointError::Corrupted),
+        }
+    }
+
+    /// Reads bytes written with [`Writer::bytes`], unless what is left is too
+    /// short to hold them, as happens to a frame whose writing was cut short.
+    pub fn frame(&mut self) -> Option<&'a [u8]> {
+        let mut reader = Reader::new(self.bytes);
+        let frame = reader.bytes().ok()?;
+        self.bytes = reader.bytes;
+        Some(frame)
+    }
+
+    /// Reads a count, then that many values with `read`.
+    pub fn vec<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T, CheckpointError>,
+    ) -> Result<Vec<T>, CheckpointError> {
+        let len = self.count()?;
+        (0..len).map(|_| read(self)).collect()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CheckpointError> {
+        if self.bytes.len() < len {
+            return Err(CheckpointError::Corrupted);
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state_machine_driver::checkpoint::CheckpointError;
+
+    use super::{Reader, Writer};
+
+    #[test]
+    fn values_are_read_as_they_were_written() {
+        let mut writer = Writer::default();
+        writer.u32(7);
+        writer.string("label");
+        writer.option_bytes(None);
+        writer.option_bytes(Some(&[4, 5]));
+        writer.bytes(&[1, 2, 3]);
+
+        let mut reader = Reader::new(&writer.bytes);
+        assert_eq!(reader.u32().unwrap(), 7);
+        assert_eq!(reader.string().unwrap(), "label");
+        assert_eq!(reader.option_bytes().unwrap(), None);
+        assert_eq!(reader.option_bytes().unwrap(), Some([4, 5].as_slice()));
+        assert_eq!(reader.bytes().unwrap(), [1, 2, 3]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn frames_cut_short_are_left_out() {
+        let mut writer = Writer::default();
+        writer.bytes(&[1, 2]);
+        writer.bytes(&[3, 4, 5]);
+
+        let mut reader = Reader::new(&writer.bytes[..writer.bytes.len() - 1]);
+        assert_eq!(reader.frame(), Some([1, 2].as_slice()));
+        assert_eq!(reader.frame(), None);
+    }
+
+    #[test]
+    fn truncated_bytes_are_rejected() {
+        let mut writer = Writer::default();
+        writer.string("label");
+
+        let mut reader = Reader::new(&writer.bytes[..6]);
+        assert!(matches!(reader.string(), Err(CheckpointError::Corrupted)));
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Mutex,
+};
+
+use crate::global_state::GlobalState;
+
+/// Set of visited states that can be shared between workers. States are
+/// spread over independently locked shards so that workers inserting
+/// different states rarely wait for each other.
+pub struct ConcurrentVisitedSet {
+    shards: Vec<Mutex<HashSet<GlobalState>>>,
+}
+
+impl ConcurrentVisitedSet {
+    pub fn new(shard_count: usize) -> Self {
+        Self {
+            shards: (0..shard_count.max(1))
+                .map(|_| Mutex::new(HashSet::new()))
+                .collect(),
+        }
+    }
+
+    /// Returns whether `global_state` had not been visited before.
+    pub fn insert(&self, global_state: GlobalState) -> bool {
+        let mut hasher = DefaultHasher::new();
+        global_state.hash(&mut hasher);
+        let shard = (hasher.finish() % self.shards.len() as u64) as usize;
+
+        self.shards[shard].lock().unwrap().insert(global_state)
+    }
+
+    pub fn into_states(self) -> HashSet<GlobalState> {
+        self.shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use crate::test_utils::test_graph::global_state;
+
+    use super::ConcurrentVisitedSet;
+
+    #[test]
+    fn a_state_is_only_inserted_once() {
+        let visited = ConcurrentVisitedSet::new(4);
+
+        assert!(visited.insert(global_state(1)));
+        assert!(!visited.insert(global_state(1)));
+        assert!(visited.insert(global_state(2)));
+
+        assert_eq!(
+            visited.into_states(),
+            HashSet::from([global_state(1), global_state(2)])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_inserts_of_the_same_state_succeed_once() {
+        let visited = Arc::new(ConcurrentVisitedSet::new(4));
+
+        let handles = (0..8)
+            .map(|_| {
+                let visited = visited.clone();
+                tokio::spawn(async move {
+                    (0..50)
+                        .filter(|value| visited.insert(global_state(*value)))
+                        .count()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut inserted = 0;
+        for handle in handles {
+            inserted += handle.await.unwrap();
+        }
+
+        assert_eq!(inserted, 50);
+    }
+}
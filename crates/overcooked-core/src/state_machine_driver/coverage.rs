@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::{
+    action::{ActionResult, ActionTemplate},
+    actor::{self, local_state::LocalState},
+    global_state::GlobalState,
+    transition::Transition,
+};
+
+/// Which actions of a model were executed during a run and how many local
+/// states its actors went through, to spot actions that never fire.
+///
+/// Every execution of an action from an explored state is counted, including
+/// the ones leading to transitions partial order reduction does not follow
+/// and the failures the failed action policy drops. States explored again,
+/// e.g. by later rounds of iterative deepening, count again.
+#[derive(Debug, Default)]
+pub struct CoverageReport {
+    /// Every action of the model, ordered by performer, label and receiver.
+    pub actions: Vec<ActionCoverage>,
+    /// Number of distinct local states each actor was found in.
+    pub local_state_counts: BTreeMap<actor::Id, usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionCoverage {
+    pub action_template: ActionTemplate,
+    /// Number of executions of the action that succeeded.
+    pub successes: usize,
+    /// Number of executions of the action that failed, panicked or timed
+    /// out.
+    pub failures: usize,
+    pub error_messages: BTreeSet<String>,
+}
+
+/// Gathers a [`CoverageReport`] while a run goes.
+#[derive(Debug, Default)]
+pub struct CoverageTracker {
+    actions: HashMap<ActionTemplate, ActionCoverage>,
+    local_states: BTreeMap<actor::Id, HashSet<LocalState>>,
+}
+
+impl CoverageTracker {
+    /// None of `action_templates` has been executed yet.
+    pub fn new(action_templates: &[ActionTemplate]) -> Self {
+        Self {
+            actions: action_templates
+                .iter()
+                .map(|action_template| {
+                    (
+                        action_template.clone(),
+                        ActionCoverage::new(action_template),
+                    )
+                })
+                .collect(),
+            local_states: BTreeMap::new(),
+        }
+    }
+
+    /// Counts the execution of the action of `transition`, whether the
+    /// transition is followed, or dropped along with its failure.
+    pub fn record_execution(&mut self, transition: &Transition) {
+        let coverage = self
+            .actions
+            .entry(transition.action_template.clone())
+            .or_insert_with(|| ActionCoverage::new(&transition.action_template));
+        match &transition.action_result {
+            ActionResult::Succeeded => coverage.successes += 1,
+            action_result => {
+                coverage.failures += 1;
+                coverage.error_messages.insert(action_result.to_string());
+            }
+        }
+    }
+
+    pub fn record_state(&mut self, global_state: &GlobalState) {
+        for (actor_id, local_state) in global_state.local_states() {
+            self.local_states
+                .entry(actor_id.clone())
+                .or_default()
+                .insert(local_state.clone());
+        }
+    }
+
+    pub fn report(&self) -> CoverageReport {
+        let mut actions: Vec<ActionCoverage> = self.actions.values().cloned().collect();
+        actions.sort_by(|a, b| {
+            let (a, b) = (&a.action_template, &b.action_template);
+            (&a.performer_id, &a.label, a.receiver_ids()).cmp(&(
+                &b.performer_id,
+                &b.label,
+                b.receiver_ids(),
+            ))
+        });
+
+        CoverageReport {
+            actions,
+            local_state_counts: self
+                .local_states
+                .iter()
+                .map(|(actor_id, local_states)| (actor_id.clone(), local_states.len()))
+                .collect(),
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Actions that were never executed, because they were never enabled in
+    /// an explored state.
+    pub fn never_executed(&self) -> impl Iterator<Item = &ActionTemplate> {
+        self.actions
+            .iter()
+            .filter(|coverage| coverage.successes == 0 && coverage.failures == 0)
+            .map(|coverage| &coverage.action_template)
+    }
+
+    /// Actions that were executed but never succeeded.
+    pub fn always_failed(&self) -> impl Iterator<Item = &ActionTemplate> {
+        self.actions
+            .iter()
+            .filter(|coverage| coverage.successes == 0 && coverage.failures > 0)
+            .map(|coverage| &coverage.action_template)
+    }
+
+    /// Error messages of the failed actions, gathered by label.
+    pub fn error_messages_by_label(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut error_messages: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for coverage in &self.actions {
+            if coverage.error_messages.is_empty() {
+                continue;
+            }
+            error_messages
+                .entry(&coverage.action_template.label)
+                .or_default()
+                .extend(coverage.error_messages.iter().map(String::as_str));
+        }
+        error_messages
+    }
+}
+
+impl ActionCoverage {
+    fn new(action_template: &ActionTemplate) -> Self {
+        Self {
+            action_template: action_template.clone(),
+            successes: 0,
+            failures: 0,
+            error_messages: BTreeSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use crate::test_utils::test_graph::{
+        ACTOR_ID, action, failed_transition, global_state, transition,
+    };
+
+    use super::CoverageTracker;
+
+    /// ```text
+    ///    ┌──────┐ a ┌──────┐
+    ///    │ GS_0 ├───► GS_1 │
+    ///    └┬──▲──┘   └┬──▲──┘
+    ///     └──┘b!     └──┘b!
+    /// ```
+    /// where `b!` fails, and `c` never fires.
+    fn tracker() -> CoverageTracker {
+        let mut tracker = CoverageTracker::new(&[action("c"), action("b"), action("a")]);
+        for transition in [
+            transition(0, 1, "a"),
+            failed_transition(0, 0, "b"),
+            failed_transition(1, 1, "b"),
+        ] {
+            tracker.record_execution(&transition);
+            tracker.record_state(&transition.from);
+            tracker.record_state(&transition.to);
+        }
+        tracker
+    }
+
+    #[test]
+    fn actions_are_told_apart_by_how_they_were_covered() {
+        let report = tracker().report();
+
+        assert_eq!(
+            report
+                .actions
+                .iter()
+                .map(|coverage| (
+                    coverage.action_template.label.as_str(),
+                    coverage.successes,
+                    coverage.failures
+                ))
+                .collect::<Vec<_>>(),
+            vec![("a", 1, 0), ("b", 0, 2), ("c", 0, 0)]
+        );
+        assert_eq!(
+            report.never_executed().cloned().collect::<Vec<_>>(),
+            vec![action("c")]
+        );
+        assert_eq!(
+            report.always_failed().cloned().collect::<Vec<_>>(),
+            vec![action("b")]
+        );
+        assert_eq!(
+            report.error_messages_by_label(),
+            BTreeMap::from([("b", BTreeSet::from(["TestActor1Error"]))])
+        );
+    }
+
+    #[test]
+    fn executions_are_counted_every_time() {
+        let mut tracker = tracker();
+        tracker.record_execution(&transition(0, 1, "a"));
+
+        assert_eq!(tracker.report().actions[0].successes, 2);
+    }
+
+    #[test]
+    fn distinct_local_states_are_counted_per_actor() {
+        let mut tracker = tracker();
+        tracker.record_state(&global_state(2));
+        tracker.record_state(&global_state(2));
+
+        assert_eq!(
+            tracker.report().local_state_counts,
+            BTreeMap::from([(ACTOR_ID.clone(), 3)])
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+
+use crate::{
+    global_state::{GlobalState, StatePredicate},
+    transition::Transition,
+};
+
+/// Enables the detection of states in which nothing useful can happen any
+/// more, i.e. every enabled action failed or leads back to the state.
+#[derive(Clone, Default)]
+pub struct DeadlockDetection {
+    /// Marks the states the model is expected to end in, such as every
+    /// resource manager having committed or aborted. Such states are reported
+    /// as terminal states rather than as deadlocks.
+    pub is_terminal: Option<StatePredicate>,
+}
+
+/// A state that is stuck without being an expected terminal state, together
+/// with the trace leading to it.
+#[derive(Debug, Clone)]
+pub struct Deadlock {
+    pub state: GlobalState,
+    pub trace: Vec<Transition>,
+}
+
+impl DeadlockDetection {
+    pub fn is_terminal(&self, global_state: &GlobalState) -> bool {
+        self.is_terminal
+            .as_ref()
+            .is_some_and(|is_terminal| is_terminal(global_state))
+    }
+}
+
+impl Deadlock {
+    /// The state `trace` starts from.
+    pub fn initial_state(&self) -> &GlobalState {
+        self.trace
+            .first()
+            .map_or(&self.state, |transition| &transition.from)
+    }
+}
+
+impl std::fmt::Debug for DeadlockDetection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeadlockDetection")
+            .field("is_terminal", &self.is_terminal.is_some())
+            .finish()
+    }
+}
+
+/// Whether none of the `out_going_transitions` of `from` makes progress.
+pub fn is_stuck(from: &GlobalState, out_going_transitions: &HashSet<Transition>) -> bool {
+    out_going_transitions
+        .iter()
+        .all(|transition| !transition.action_result.is_success() || transition.to == *from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use crate::test_utils::test_graph::{failed_transition, global_state, transition, value_of};
+
+    use super::{DeadlockDetection, is_stuck};
+
+    #[test]
+    fn state_without_transitions_is_stuck() {
+        assert!(is_stuck(&global_state(0), &HashSet::new()));
+    }
+
+    #[test]
+    fn state_with_only_failures_and_self_loops_is_stuck() {
+        assert!(is_stuck(
+            &global_state(0),
+            &HashSet::from([transition(0, 0, "a"), failed_transition(0, 1, "b")])
+        ));
+    }
+
+    #[test]
+    fn state_with_a_successful_transition_elsewhere_is_not_stuck() {
+        assert!(!is_stuck(
+            &global_state(0),
+            &HashSet::from([transition(0, 0, "a"), transition(0, 1, "b")])
+        ));
+    }
+
+    #[test]
+    fn terminal_states_are_marked_by_predicate() {
+        let detection = DeadlockDetection {
+            is_terminal: Some(Arc::new(|global_state| value_of(global_state) == 1)),
+        };
+
+        assert!(detection.is_terminal(&global_state(1)));
+        assert!(!detection.is_terminal(&global_state(2)));
+        assert!(!DeadlockDetection::default().is_terminal(&global_state(1)));
+    }
+}
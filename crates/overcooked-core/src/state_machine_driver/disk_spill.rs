@@ -0,0 +1,99 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::state_machine_driver::checkpoint::{ActionOutputCodec, LocalStateCodec};
+
+pub mod node_codec;
+pub mod trace_log;
+
+static NEXT_DIRECTORY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Where and when data kept by a run is moved from memory to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskSpill {
+    /// Local directory under which the spilled data is written.
+    pub directory: PathBuf,
+    /// Number of bytes of encoded data kept in memory before spilling to
+    /// disk.
+    pub memory_threshold: usize,
+}
+
+/// Turns the local states of spilled nodes, and what the actions of their
+/// traces returned or failed with, into bytes and back.
+#[derive(Clone)]
+pub struct SpillCodecs {
+    pub codec: Arc<dyn LocalStateCodec>,
+    pub action_output_codec: Arc<dyn ActionOutputCodec>,
+}
+
+/// A directory only used by a single spilling data structure, which is
+/// removed along with its content when dropped.
+#[derive(Debug)]
+pub struct SpillDirectory {
+    path: PathBuf,
+}
+
+impl DiskSpill {
+    pub fn create_directory(&self, name: &str) -> io::Result<SpillDirectory> {
+        let path = self.directory.join(format!(
+            "overcooked-{name}-{}-{}",
+            std::process::id(),
+            NEXT_DIRECTORY_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path)?;
+        Ok(SpillDirectory { path })
+    }
+}
+
+impl SpillDirectory {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file(&self, name: impl std::fmt::Display) -> PathBuf {
+        self.path.join(name.to_string())
+    }
+}
+
+impl std::fmt::Debug for SpillCodecs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpillCodecs").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SpillDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::DiskSpill;
+
+    #[test]
+    fn spill_directories_are_removed_once_dropped() {
+        let disk_spill = DiskSpill {
+            directory: env::temp_dir(),
+            memory_threshold: 0,
+        };
+        let directory = disk_spill.create_directory("test").unwrap();
+        let other_directory = disk_spill.create_directory("test").unwrap();
+        fs::write(directory.file("data"), [1, 2, 3]).unwrap();
+        let path = directory.path().to_path_buf();
+
+        assert_ne!(path, other_directory.path());
+        assert!(directory.file("data").exists());
+        drop(directory);
+        assert!(!path.exists());
+        assert!(other_directory.path().exists());
+    }
+}
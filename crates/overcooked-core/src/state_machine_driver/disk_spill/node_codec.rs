@@ -0,0 +1,476 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    io,
+    time::Duration,
+};
+
+use crate::{
+    action::{ActionResult, ActionTemplate},
+    actor,
+    global_state::GlobalState,
+    state_machine_driver::{
+        disk_spill::{DiskSpill, SpillCodecs, trace_log::TraceLog},
+        search_strategy::SearchNode,
+        trace::Trace,
+    },
+    transition::Transition,
+};
+
+/// Encodes [`SearchNode`]s as bytes. Local states, outputs and errors are
+/// written through [`SpillCodecs`], while actor ids and action templates, of
+/// which a model has few, are kept in memory once and referred to by their
+/// index.
+///
+/// Every transition of a trace is written once, to a [`TraceLog`], along with
+/// the offset of the transition before it, and a node only refers to the last
+/// transition of its trace. Encoding the successors of the latest decoded node
+/// thus only writes their own transition.
+pub struct NodeCodec {
+    codecs: SpillCodecs,
+    actor_ids: Interner<actor::Id>,
+    action_templates: Interner<ActionTemplate>,
+    trace_log: TraceLog,
+    /// The offset of every transition of the trace of the latest decoded
+    /// node, along with the trace ending with it, by depth.
+    latest_trace: Vec<(u64, Trace)>,
+}
+
+struct Interner<T> {
+    indices: HashMap<T, u32>,
+    values: Vec<T>,
+}
+
+/// Reads back the values written by a [`NodeCodec`].
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl NodeCodec {
+    pub fn new(disk_spill: DiskSpill, codecs: SpillCodecs) -> Self {
+        Self {
+            codecs,
+            actor_ids: Interner::default(),
+            action_templates: Interner::default(),
+            trace_log: TraceLog::new(disk_spill),
+            latest_trace: Vec::new(),
+        }
+    }
+
+    /// Appends `node` to `bytes`, writing the transitions of its trace that
+    /// have not been written yet.
+    pub fn encode(&mut self, node: &SearchNode, bytes: &mut Vec<u8>) -> io::Result<()> {
+        let offset = self.write_trace(&node.trace)?;
+        push_u64(bytes, offset.map_or(0, |offset| offset + 1));
+        push(bytes, node.trace.len() as u32);
+        self.encode_state(&node.state, bytes);
+        Ok(())
+    }
+
+    /// Takes the next node off `decoder`.
+    pub fn decode(&mut self, decoder: &mut Decoder) -> io::Result<SearchNode> {
+        let (node, latest_trace) = self.decode_node(decoder)?;
+        self.latest_trace = latest_trace;
+        Ok(node)
+    }
+
+    /// Same as [`NodeCodec::decode`], leaving the latest decoded node as it
+    /// is.
+    pub fn peek(&self, decoder: &mut Decoder) -> io::Result<SearchNode> {
+        self.decode_node(decoder).map(|(node, _)| node)
+    }
+
+    fn decode_node(&self, decoder: &mut Decoder) -> io::Result<(SearchNode, Vec<(u64, Trace)>)> {
+        let reference = decoder.u64();
+        let depth = decoder.next() as usize;
+        let state = self.decode_state(decoder)?;
+        let steps = self.read_trace(reference.checked_sub(1), depth)?;
+        let trace = steps
+            .last()
+            .map_or_else(Trace::default, |(_, trace)| trace.clone());
+        Ok((SearchNode { state, trace }, steps))
+    }
+
+    /// Writes the transitions of `trace` that the trace log lacks, and returns
+    /// the offset of the last one.
+    fn write_trace(&mut self, trace: &Trace) -> io::Result<Option<u64>> {
+        let mut unwritten = Vec::new();
+        let mut curr = trace;
+        let parent = loop {
+            if curr.is_empty() {
+                break None;
+            }
+            if let Some((offset, latest)) = self.latest_trace.get(curr.len() - 1)
+                && latest.ptr_eq(curr)
+            {
+                break Some(*offset);
+            }
+            unwritten.push(curr);
+            curr = curr.previous().unwrap();
+        };
+
+        unwritten
+            .into_iter()
+            .rev()
+            .try_fold(parent, |parent, trace| {
+                let mut record = Vec::new();
+                push_u64(&mut record, parent.map_or(0, |parent| parent + 1));
+                let transition = trace.last().unwrap();
+                if parent.is_none() {
+                    self.encode_state(&transition.from, &mut record);
+                }
+                self.encode_transition(transition, &mut record);
+                self.trace_log.append(&record).map(Some)
+            })
+    }
+
+    /// The transitions of the trace of `depth` transitions ending with the one
+    /// at `offset`, along with the trace ending with each of them, sharing the
+    /// ones it has in common with the trace of the latest decoded node.
+    fn read_trace(&self, offset: Option<u64>, depth: usize) -> io::Result<Vec<(u64, Trace)>> {
+        let mut unread = Vec::new();
+        let mut curr = (offset, depth);
+        while let (Some(offset), depth) = curr
+            && self
+                .latest_trace
+                .get(depth - 1)
+                .is_none_or(|(latest, _)| *latest != offset)
+        {
+            let record = self.trace_log.read(offset)?;
+            let parent = Decoder::new(&record).u64().checked_sub(1);
+            unread.push((offset, record));
+            curr = (parent, depth - 1);
+        }
+
+        let mut steps = self.latest_trace[..curr.1].to_vec();
+        for (offset, record) in unread.into_iter().rev() {
+            let mut decoder = Decoder::new(&record);
+            decoder.u64();
+            let trace = steps
+                .last()
+                .map_or_else(Trace::default, |(_, trace)| trace.clone());
+            let from = match trace.last() {
+                Some(transition) => transition.to.clone(),
+                None => self.decode_state(&mut decoder)?,
+            };
+            let transition = self.decode_transition(from, &mut decoder)?;
+            steps.push((offset, trace.extend(transition)));
+        }
+        Ok(steps)
+    }
+
+    fn encode_state(&mut self, global_state: &GlobalState, bytes: &mut Vec<u8>) {
+        let local_states: Vec<_> = global_state.local_states().collect();
+        push(bytes, local_states.len() as u32);
+        for (actor_id, local_state) in local_states {
+            push(bytes, self.actor_ids.intern(actor_id));
+            push_bytes(bytes, &self.codecs.codec.encode(actor_id, local_state));
+        }
+    }
+
+    fn decode_state(&self, decoder: &mut Decoder) -> io::Result<GlobalState> {
+        let actor_count = decoder.next();
+        let local_states = (0..actor_count)
+            .map(|_| {
+                let actor_id = self.actor_ids.get(decoder.next()).clone();
+                let local_state = self
+                    .codecs
+                    .codec
+                    .decode(&actor_id, decoder.bytes())
+                    .ok_or_else(|| undecodable("local state"))?;
+                Ok((actor_id, local_state))
+            })
+            .collect::<io::Result<BTreeMap<_, _>>>()?;
+        Ok(GlobalState::new(local_states))
+    }
+
+    fn encode_transition(&mut self, transition: &Transition, bytes: &mut Vec<u8>) {
+        let action_template = &transition.action_template;
+        push(bytes, self.action_templates.intern(action_template));
+        match &transition.action_result {
+            ActionResult::Succeeded => push(bytes, 0),
+            ActionResult::Failed(error) => {
+                push(bytes, 1);
+                push_bytes(
+                    bytes,
+                    &self
+                        .codecs
+                        .action_output_codec
+                        .encode_error(action_template, error.as_ref()),
+                );
+            }
+            ActionResult::Panicked(message) => {
+                push(bytes, 2);
+                push_bytes(bytes, message.as_bytes());
+            }
+            ActionResult::TimedOut(timeout) => {
+                push(bytes, 3);
+                push_u64(bytes, timeout.as_nanos() as u64);
+            }
+        }
+        match &transition.action_output {
+            Some(action_output) => {
+                push(bytes, 1);
+                push_bytes(
+                    bytes,
+                    &self
+                        .codecs
+                        .action_output_codec
+                        .encode_output(action_template, action_output),
+                );
+            }
+            None => push(bytes, 0),
+        }
+        self.encode_state(&transition.to, bytes);
+    }
+
+    fn decode_transition(
+        &self,
+        from: GlobalState,
+        decoder: &mut Decoder,
+    ) -> io::Result<Transition> {
+        let action_template = self.action_templates.get(decoder.next()).clone();
+        let output_codec = &self.codecs.action_output_codec;
+        let action_result = match decoder.next() {
+            0 => ActionResult::Succeeded,
+            1 => ActionResult::Failed(
+                output_codec
+                    .decode_error(&action_template, decoder.bytes())
+                    .ok_or_else(|| undecodable("error"))?,
+            ),
+            2 => ActionResult::Panicked(String::from_utf8_lossy(decoder.bytes()).into_owned()),
+            _ => ActionResult::TimedOut(Duration::from_nanos(decoder.u64())),
+        };
+        let action_output = match decoder.next() {
+            0 => None,
+            _ => Some(
+                output_codec
+                    .decode_output(&action_template, decoder.bytes())
+                    .ok_or_else(|| undecodable("output"))?,
+            ),
+        };
+        Ok(Transition {
+            from,
+            to: self.decode_state(decoder)?,
+            action_template,
+            action_result,
+            action_output,
+        })
+    }
+}
+
+fn undecodable(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("a spilled {what} could not be decoded"),
+    )
+}
+
+impl<T: Clone + Eq + Hash> Interner<T> {
+    fn intern(&mut self, value: &T) -> u32 {
+        if let Some(index) = self.indices.get(value) {
+            return *index;
+        }
+        let index = self.values.len() as u32;
+        self.values.push(value.clone());
+        self.indices.insert(value.clone(), index);
+        index
+    }
+
+    fn get(&self, index: u32) -> &T {
+        &self.values[index as usize]
+    }
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            indices: HashMap::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn next(&mut self) -> u32 {
+        u32::from_le_bytes(self.take(4).try_into().unwrap())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take(8).try_into().unwrap())
+    }
+
+    fn bytes(&mut self) -> &'a [u8] {
+        let len = self.next() as usize;
+        self.take(len)
+    }
+
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        value
+    }
+}
+
+fn push(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_bytes(bytes: &mut Vec<u8>, value: &[u8]) {
+    push(bytes, value.len() as u32);
+    bytes.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, sync::Arc};
+
+    use test_case::test_case;
+
+    use crate::{
+        action::ActionResult,
+        state_machine_driver::{
+            disk_spill::{DiskSpill, SpillCodecs},
+            search_strategy::SearchNode,
+            trace::Trace,
+        },
+        test_utils::{
+            test_actors::TestActor1Error,
+            test_graph::{
+                TestActor1StateCodec, TestOutputCodec, failed_transition, global_state,
+                panicked_transition, transition, value_of,
+            },
+        },
+    };
+
+    use super::{Decoder, NodeCodec};
+
+    fn codec(memory_threshold: usize) -> NodeCodec {
+        NodeCodec::new(
+            DiskSpill {
+                directory: env::temp_dir(),
+                memory_threshold,
+            },
+            SpillCodecs {
+                codec: Arc::new(TestActor1StateCodec),
+                action_output_codec: Arc::new(TestOutputCodec),
+            },
+        )
+    }
+
+    #[test_case(0 ; "traces spilled")]
+    #[test_case(usize::MAX ; "traces kept in memory")]
+    fn nodes_are_decoded_as_they_were_encoded(memory_threshold: usize) {
+        let trace = Trace::default()
+            .extend(transition(0, 1, "a"))
+            .extend(failed_transition(1, 1, "b"))
+            .extend(panicked_transition(1, "c"))
+            .extend(transition(1, 2, "c"));
+        let mut codec = codec(memory_threshold);
+        let mut bytes = Vec::new();
+        codec
+            .encode(
+                &SearchNode {
+                    state: global_state(2),
+                    trace: trace.clone(),
+                },
+                &mut bytes,
+            )
+            .unwrap();
+        codec
+            .encode(
+                &SearchNode {
+                    state: global_state(0),
+                    trace: Trace::default(),
+                },
+                &mut bytes,
+            )
+            .unwrap();
+
+        let mut decoder = Decoder::new(&bytes);
+        let node = codec.decode(&mut decoder).unwrap();
+        let decoded_transitions = node.trace.to_vec();
+        assert_eq!(value_of(&node.state), 2);
+        assert_eq!(decoded_transitions, trace.to_vec());
+        let ActionResult::Failed(error) = &decoded_transitions[1].action_result else {
+            panic!("the failure is not decoded as such");
+        };
+        assert!(error.downcast_ref::<TestActor1Error>().is_some());
+        assert_eq!(
+            decoded_transitions
+                .iter()
+                .map(|transition| transition.action_output.clone())
+                .collect::<Vec<_>>(),
+            trace
+                .to_vec()
+                .into_iter()
+                .map(|transition| transition.action_output)
+                .collect::<Vec<_>>()
+        );
+
+        let node = codec.decode(&mut decoder).unwrap();
+        assert_eq!(value_of(&node.state), 0);
+        assert!(node.trace.is_empty());
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn successors_of_the_latest_decoded_node_only_write_their_own_transition() {
+        let mut codec = codec(usize::MAX);
+        let mut bytes = Vec::new();
+        let trace = (0..10).fold(Trace::default(), |trace, value| {
+            trace.extend(transition(value, value + 1, "a"))
+        });
+        codec
+            .encode(
+                &SearchNode {
+                    state: global_state(10),
+                    trace,
+                },
+                &mut bytes,
+            )
+            .unwrap();
+        assert_eq!(codec.trace_log.len(), 10);
+        let node = codec.decode(&mut Decoder::new(&bytes)).unwrap();
+
+        let mut bytes = Vec::new();
+        for (value, label) in [(11, "a"), (12, "b")] {
+            codec
+                .encode(
+                    &SearchNode {
+                        state: global_state(value),
+                        trace: node.trace.extend(transition(10, value, label)),
+                    },
+                    &mut bytes,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(codec.trace_log.len(), 12);
+        let mut decoder = Decoder::new(&bytes);
+        for (value, label) in [(11, "a"), (12, "b")] {
+            let successor = codec.decode(&mut decoder).unwrap();
+            assert_eq!(value_of(&successor.state), value);
+            assert_eq!(successor.trace.len(), 11);
+            assert_eq!(successor.trace.last(), Some(&transition(10, value, label)));
+            assert!(successor.trace.previous().unwrap().ptr_eq(&node.trace));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+use crate::state_machine_driver::disk_spill::{DiskSpill, SpillDirectory};
+
+/// Records appended once and read back by their offset, kept in memory until
+/// they take more than the memory threshold, then appended to a file.
+pub struct TraceLog {
+    disk_spill: DiskSpill,
+    /// Only created once records are spilled.
+    directory: Option<SpillDirectory>,
+    file: Option<File>,
+    file_len: u64,
+    /// Records appended after the ones of `file`.
+    buffer: Vec<u8>,
+    record_count: usize,
+}
+
+impl TraceLog {
+    pub fn new(disk_spill: DiskSpill) -> Self {
+        Self {
+            disk_spill,
+            directory: None,
+            file: None,
+            file_len: 0,
+            buffer: Vec::new(),
+            record_count: 0,
+        }
+    }
+
+    /// Appends `record` and returns its offset.
+    pub fn append(&mut self, record: &[u8]) -> io::Result<u64> {
+        let offset = self.file_len + self.buffer.len() as u64;
+        self.buffer
+            .extend_from_slice(&(record.len() as u32).to_le_bytes());
+        self.buffer.extend_from_slice(record);
+        self.record_count += 1;
+        if self.buffer.len() > self.disk_spill.memory_threshold {
+            self.spill()?;
+        }
+        Ok(offset)
+    }
+
+    /// The record appended at `offset`.
+    pub fn read(&self, offset: u64) -> io::Result<Vec<u8>> {
+        if let Some(start) = offset.checked_sub(self.file_len) {
+            let start = start as usize;
+            let len = u32::from_le_bytes(self.buffer[start..start + 4].try_into().unwrap());
+            return Ok(self.buffer[start + 4..start + 4 + len as usize].to_vec());
+        }
+
+        let mut file = self
+            .file
+            .as_ref()
+            .expect("records before the buffer are spilled");
+        file.seek(SeekFrom::Start(offset))?;
+        let mut len = [0; 4];
+        file.read_exact(&mut len)?;
+        let mut record = vec![0; u32::from_le_bytes(len) as usize];
+        file.read_exact(&mut record)?;
+        Ok(record)
+    }
+
+    pub fn len(&self) -> usize {
+        self.record_count
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        if self.file.is_none() {
+            let directory = self.disk_spill.create_directory("traces")?;
+            self.file = Some(
+                OpenOptions::new()
+                    .read(true)
+                    .append(true)
+                    .create(true)
+                    .open(directory.file("records"))?,
+            );
+            self.directory = Some(directory);
+        }
+        self.file.as_mut().unwrap().write_all(&self.buffer)?;
+        self.file_len += self.buffer.len() as u64;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use test_case::test_case;
+
+    use crate::state_machine_driver::disk_spill::DiskSpill;
+
+    use super::TraceLog;
+
+    #[test_case(0 ; "every record spilled")]
+    #[test_case(16 ; "some records spilled")]
+    #[test_case(usize::MAX ; "nothing spilled")]
+    fn records_are_read_back_by_their_offset(memory_threshold: usize) {
+        let mut trace_log = TraceLog::new(DiskSpill {
+            directory: env::temp_dir(),
+            memory_threshold,
+        });
+        let offsets = (0..5u8)
+            .map(|value| trace_log.append(&vec![value; value as usize]).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(trace_log.len(), 5);
+        for (value, offset) in offsets.into_iter().enumerate().rev() {
+            assert_eq!(trace_log.read(offset).unwrap(), vec![value as u8; value]);
+        }
+        assert_eq!(trace_log.directory.is_some(), memory_threshold < usize::MAX);
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+/// Bounds of a run. Limits left as `None` are not enforced.
+///
+/// The limits on distinct states, followed transitions and elapsed time are
+/// checked before each state is explored, so a run may overshoot them by the
+/// successors of a single state.
+#[derive(Debug, Clone, Default)]
+pub struct ExplorationBudget {
+    /// States reached at this depth are checked but not explored any further.
+    pub max_depth: Option<usize>,
+    pub max_states: Option<usize>,
+    pub max_transitions: Option<usize>,
+    pub time_limit: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLimit {
+    Depth,
+    States,
+    Transitions,
+    Time,
+}
+
+/// Why a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Termination {
+    #[default]
+    Completed,
+    /// An invariant was violated or a deadlock was found.
+    ViolationFound,
+    BudgetExhausted(BudgetLimit),
+    /// Data spilled to disk could not be written or read back.
+    SpillFailed,
+}
+
+impl ExplorationBudget {
+    /// Returns the first limit, other than the depth, that has been reached.
+    pub fn exhausted_limit(
+        &self,
+        state_count: usize,
+        transition_count: usize,
+        elapsed: Duration,
+    ) -> Option<BudgetLimit> {
+        if self.max_states.is_some_and(|max| state_count >= max) {
+            Some(BudgetLimit::States)
+        } else if self
+            .max_transitions
+            .is_some_and(|max| transition_count >= max)
+        {
+            Some(BudgetLimit::Transitions)
+        } else if self.time_limit.is_some_and(|limit| elapsed >= limit) {
+            Some(BudgetLimit::Time)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_beyond_max_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max| depth >= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_case::test_case;
+
+    use super::{BudgetLimit, ExplorationBudget};
+
+    #[test]
+    fn unbounded_budget_is_never_exhausted() {
+        let budget = ExplorationBudget::default();
+
+        assert_eq!(
+            budget.exhausted_limit(usize::MAX, usize::MAX, Duration::MAX),
+            None
+        );
+        assert!(!budget.is_beyond_max_depth(usize::MAX));
+    }
+
+    #[rustfmt::skip]
+    //          states, transitions, elapsed, expected
+    #[test_case(9,      99,          9,       None)]
+    #[test_case(10,     99,          9,       Some(BudgetLimit::States))]
+    #[test_case(9,      100,         9,       Some(BudgetLimit::Transitions))]
+    #[test_case(9,      99,          10,      Some(BudgetLimit::Time))]
+    #[test_case(10,     100,         10,      Some(BudgetLimit::States))]
+    fn exhausted_limit_works(
+        state_count: usize,
+        transition_count: usize,
+        elapsed_secs: u64,
+        expected: Option<BudgetLimit>,
+    ) {
+        let budget = ExplorationBudget {
+            max_depth: None,
+            max_states: Some(10),
+            max_transitions: Some(100),
+            time_limit: Some(Duration::from_secs(10)),
+        };
+
+        assert_eq!(
+            budget.exhausted_limit(
+                state_count,
+                transition_count,
+                Duration::from_secs(elapsed_secs)
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn depth_limit_works() {
+        let budget = ExplorationBudget {
+            max_depth: Some(2),
+            ..ExplorationBudget::default()
+        };
+
+        assert!(!budget.is_beyond_max_depth(1));
+        assert!(budget.is_beyond_max_depth(2));
+    }
+}
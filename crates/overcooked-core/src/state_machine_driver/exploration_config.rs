@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use crate::state_machine_driver::{
+    checkpoint::Checkpointing,
+    deadlock::DeadlockDetection,
+    exploration_budget::ExplorationBudget,
+    exploration_observer::ExplorationObserver,
+    invariant::{Invariant, ViolationPolicy},
+    liveness::{LivenessProperty, fairness::FairnessConstraint},
+    partial_order_reduction::PartialOrderReduction,
+    search_strategy::SearchStrategyType,
+    symmetry::Symmetry,
+    visited_set::VisitedSetType,
+};
+
+/// Settings of a single [`StateMachineDriver`](super::StateMachineDriver) run.
+#[derive(Debug, Clone, Default)]
+pub struct ExplorationConfig {
+    /// Checked on every newly discovered state, including the initial one.
+    pub invariants: Vec<Invariant>,
+    pub violation_policy: ViolationPolicy,
+    pub search_strategy: SearchStrategyType,
+    pub budget: ExplorationBudget,
+    /// Deadlocks are only looked for when this is set.
+    pub deadlock_detection: Option<DeadlockDetection>,
+    /// Checked on the explored state machine once the exploration is over.
+    pub liveness_properties: Vec<LivenessProperty>,
+    /// Assumed when looking for runs violating the liveness properties.
+    pub fairness_constraints: Vec<FairnessConstraint>,
+    /// Every interleaving of the actions is explored unless this is set, and
+    /// no liveness property is to be checked.
+    pub partial_order_reduction: Option<PartialOrderReduction>,
+    pub symmetry: Option<Symmetry>,
+    pub visited_set: VisitedSetType,
+    /// Leaves the transitions of the result out, only counting them. They are
+    /// kept all the same when liveness properties are checked or checkpoints
+    /// taken, both of which need them.
+    pub discard_transitions: bool,
+    /// Gathers a [`CoverageReport`](super::coverage::CoverageReport) into the
+    /// result. A resumed run only covers what is explored after the
+    /// checkpoint.
+    pub coverage: bool,
+    /// No checkpoint is taken unless this is set.
+    pub checkpointing: Option<Checkpointing>,
+    /// Told about the progress of the run, in the order they are listed.
+    pub observers: Vec<Arc<dyn ExplorationObserver>>,
+}
@@ -0,0 +1,33 @@
+use crate::{
+    global_state::GlobalState, state_machine_driver::exploration_result::ExplorationResult,
+    transition::Transition,
+};
+
+/// Told about the progress of a run while it goes, e.g. to draw a progress
+/// bar or export the state machine as it is explored. Every callback does
+/// nothing unless overridden.
+pub trait ExplorationObserver: Send + Sync {
+    /// Called once per round for every state that had not been visited yet,
+    /// along with the length of the trace it was discovered with.
+    fn on_state_discovered(&self, _global_state: &GlobalState, _depth: usize) {}
+
+    /// Called for every out going transition of an explored state, including
+    /// the ones partial order reduction does not follow.
+    fn on_transition_computed(&self, _transition: &Transition) {}
+
+    /// Called for every computed transition whose action failed.
+    fn on_action_failed(&self, _transition: &Transition) {}
+
+    /// Called once every state discovered at `depth` has been explored.
+    /// Breadth first searches tell it as they go, while other search
+    /// strategies only tell it once their round is over.
+    fn on_depth_completed(&self, _depth: usize) {}
+
+    fn on_run_finished(&self, _result: &ExplorationResult) {}
+}
+
+impl std::fmt::Debug for dyn ExplorationObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ExplorationObserver")
+    }
+}
@@ -0,0 +1,49 @@
+use std::{collections::HashSet, io};
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::{
+        action_fault::ActionFault, checkpoint::CheckpointError, coverage::CoverageReport,
+        deadlock::Deadlock, exploration_budget::Termination, invariant::InvariantViolation,
+        liveness::LivenessViolation, visited_set::bitstate_visited_set::BitstateReport,
+    },
+    transition::Transition,
+};
+
+/// Outcome of a [`StateMachineDriver`](super::StateMachineDriver) run.
+///
+/// When the run stops early, because of a violation or an exhausted budget,
+/// `transitions` and `visited_states` only contain what had been explored up
+/// to that point, and `frontier` holds the visited states that were never
+/// explored. With partial order reduction, `transitions` only contains the
+/// transitions that were followed. With symmetry reduction, `visited_states`
+/// only contains one representative per class of equivalent states, and it is
+/// left empty when the visited set only keeps fingerprints or bits.
+#[derive(Debug, Default)]
+pub struct ExplorationResult {
+    /// Left empty when transitions are discarded.
+    pub transitions: HashSet<Transition>,
+    /// Number of distinct transitions followed, whether they are kept or not.
+    /// Discarded transitions cannot be told apart from the ones followed
+    /// before, so they are counted each time they are followed.
+    pub transition_count: usize,
+    pub visited_states: HashSet<GlobalState>,
+    pub violations: Vec<InvariantViolation>,
+    pub deadlocks: Vec<Deadlock>,
+    pub liveness_violations: Vec<LivenessViolation>,
+    /// Transitions whose action panicked or timed out.
+    pub action_faults: Vec<ActionFault>,
+    pub terminal_states: HashSet<GlobalState>,
+    pub termination: Termination,
+    pub frontier: Vec<GlobalState>,
+    /// Estimated probability that part of the state space was missed because
+    /// two states shared a fingerprint.
+    pub fingerprint_collision_probability: Option<f64>,
+    pub bitstate_report: Option<BitstateReport>,
+    /// Set when coverage is configured.
+    pub coverage: Option<CoverageReport>,
+    /// Why the latest checkpoint that could not be written failed.
+    pub checkpoint_error: Option<CheckpointError>,
+    /// Why the run stopped with [`Termination::SpillFailed`].
+    pub spill_error: Option<io::Error>,
+}
@@ -0,0 +1,386 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    sync::Arc,
+};
+
+use crate::{
+    global_state::{GlobalState, StatePredicate},
+    state_machine_driver::{TransitionComputer, trace::Trace},
+    transition::Transition,
+};
+
+pub type TransitionCost = Arc<dyn Fn(&Transition) -> u64 + Send + Sync>;
+pub type Heuristic = Arc<dyn Fn(&GlobalState) -> u64 + Send + Sync>;
+
+/// Looks for the cheapest sequence of transitions from an initial state to a
+/// goal, computing transitions only out of the states it gets to, and stops
+/// as soon as the goal is reached.
+///
+/// Without a heuristic this is Dijkstra's algorithm, otherwise it is A*. The
+/// path found is the cheapest one as long as the heuristic never
+/// overestimates the cost left to reach the goal. Costs saturate at
+/// `u64::MAX` rather than overflow.
+///
+/// Failed transitions are followed like any other, since the model does get
+/// to the state they lead to. Panicked and timed out ones lead back to the
+/// state they were taken from, so they never make a path any cheaper.
+pub struct GoalSearch {
+    transition_computer: Box<dyn TransitionComputer>,
+    config: GoalSearchConfig,
+}
+
+#[derive(Clone)]
+pub struct GoalSearchConfig {
+    pub goal: StatePredicate,
+    /// Cost of taking a transition, which is 1 for every transition unless
+    /// set.
+    pub transition_cost: Option<TransitionCost>,
+    /// Lower bound of the cost left to reach the goal from a state.
+    pub heuristic: Option<Heuristic>,
+    /// The search gives up once this many states have been explored.
+    pub max_states: Option<usize>,
+}
+
+/// A cheapest sequence of transitions to a goal.
+#[derive(Debug, Clone)]
+pub struct GoalPath {
+    pub cost: u64,
+    pub transitions: Vec<Transition>,
+}
+
+#[derive(Debug, Default)]
+pub struct GoalSearchResult {
+    /// `None` if no goal is reachable, or none was reached within the
+    /// budget.
+    pub path: Option<GoalPath>,
+    pub explored_states: usize,
+}
+
+/// A state waiting to be explored, ordered so that the one with the lowest
+/// estimated total cost comes first out of a [`BinaryHeap`], then the one
+/// pushed first.
+struct OpenNode {
+    estimated_cost: u64,
+    order: Reverse<u64>,
+    cost: u64,
+    state: GlobalState,
+    trace: Trace,
+}
+
+impl GoalSearch {
+    pub fn new(transition_computer: Box<dyn TransitionComputer>, config: GoalSearchConfig) -> Self {
+        Self {
+            transition_computer,
+            config,
+        }
+    }
+
+    pub async fn run(&self, initial_state: GlobalState) -> GoalSearchResult {
+        let mut result = GoalSearchResult::default();
+        // Lowest cost each state has been reached at so far.
+        let mut costs: HashMap<GlobalState, u64> = HashMap::from([(initial_state.clone(), 0)]);
+        let mut open = BinaryHeap::new();
+        let mut pushed = 0;
+        open.push(self.open_node(initial_state, 0, Trace::default(), &mut pushed));
+
+        while let Some(node) = open.pop() {
+            if costs.get(&node.state).is_some_and(|cost| *cost < node.cost) {
+                // A cheaper way to this state has been found since it was pushed.
+                continue;
+            }
+            if (self.config.goal)(&node.state) {
+                result.path = Some(GoalPath {
+                    cost: node.cost,
+                    transitions: node.trace.to_vec(),
+                });
+                return result;
+            }
+            if self
+                .config
+                .max_states
+                .is_some_and(|max| result.explored_states >= max)
+            {
+                return result;
+            }
+
+            result.explored_states += 1;
+            for transition in self.transition_computer.compute(node.state.clone()).await {
+                let cost = node.cost.saturating_add(self.cost_of(&transition));
+                if costs
+                    .get(&transition.to)
+                    .is_some_and(|known| *known <= cost)
+                {
+                    continue;
+                }
+
+                costs.insert(transition.to.clone(), cost);
+                let state = transition.to.clone();
+                let trace = node.trace.extend(transition);
+                open.push(self.open_node(state, cost, trace, &mut pushed));
+            }
+        }
+
+        result
+    }
+
+    fn cost_of(&self, transition: &Transition) -> u64 {
+        self.config
+            .transition_cost
+            .as_ref()
+            .map_or(1, |transition_cost| transition_cost(transition))
+    }
+
+    fn open_node(&self, state: GlobalState, cost: u64, trace: Trace, pushed: &mut u64) -> OpenNode {
+        let estimated_cost = cost.saturating_add(
+            self.config
+                .heuristic
+                .as_ref()
+                .map_or(0, |heuristic| heuristic(&state)),
+        );
+        *pushed += 1;
+        OpenNode {
+            estimated_cost,
+            order: Reverse(*pushed),
+            cost,
+            state,
+            trace,
+        }
+    }
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (Reverse(self.estimated_cost), self.order)
+            .cmp(&(Reverse(other.estimated_cost), other.order))
+    }
+}
+
+impl std::fmt::Debug for GoalSearchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoalSearchConfig")
+            .field("transition_cost", &self.transition_cost.is_some())
+            .field("heuristic", &self.heuristic.is_some())
+            .field("max_states", &self.max_states)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use crate::{
+        action::ActionTemplate,
+        global_state::GlobalState,
+        state_machine_driver::TransitionComputer,
+        test_utils::test_graph::{
+            GraphTransitionComputer, failed_transition, global_state, transition, value_of,
+        },
+        transition::Transition,
+    };
+
+    use super::{GoalSearch, GoalSearchConfig};
+
+    /// Two ways from 0 to 3: a cheap one through 1 and 2, and an expensive
+    /// shortcut. 4 leads nowhere near the goal.
+    ///
+    /// ```text
+    ///       a(1)     a(1)     a(1)
+    ///    0 ------ 1 ------ 2 ------ 3
+    ///    |                          ^
+    ///    +------------ b(5) --------+
+    ///    |
+    ///    +-- c(1) --> 4
+    /// ```
+    fn graph() -> Vec<Transition> {
+        vec![
+            transition(0, 1, "a"),
+            transition(1, 2, "a"),
+            transition(2, 3, "a"),
+            transition(0, 3, "b"),
+            transition(0, 4, "c"),
+        ]
+    }
+
+    fn config(goal: u8) -> GoalSearchConfig {
+        GoalSearchConfig {
+            goal: Arc::new(move |global_state| value_of(global_state) == goal),
+            transition_cost: Some(Arc::new(|transition| {
+                if transition.action_template.label == "b" {
+                    5
+                } else {
+                    1
+                }
+            })),
+            heuristic: None,
+            max_states: None,
+        }
+    }
+
+    fn values_along(path: &[Transition]) -> Vec<u8> {
+        path.iter()
+            .map(|transition| value_of(&transition.to))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn cheapest_path_is_found_rather_than_the_shortest() {
+        let result = GoalSearch::new(Box::new(GraphTransitionComputer::new(graph())), config(3))
+            .run(global_state(0))
+            .await;
+
+        let path = result.path.unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(values_along(&path.transitions), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn shortest_path_is_found_without_costs() {
+        let result = GoalSearch::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            GoalSearchConfig {
+                transition_cost: None,
+                ..config(3)
+            },
+        )
+        .run(global_state(0))
+        .await;
+
+        let path = result.path.unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(values_along(&path.transitions), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn initial_goal_is_reached_without_transitions() {
+        let result = GoalSearch::new(Box::new(GraphTransitionComputer::new(graph())), config(0))
+            .run(global_state(0))
+            .await;
+
+        let path = result.path.unwrap();
+        assert_eq!(path.cost, 0);
+        assert!(path.transitions.is_empty());
+        assert_eq!(result.explored_states, 0);
+    }
+
+    #[tokio::test]
+    async fn unreachable_goal_is_reported_after_exploring_everything() {
+        let result = GoalSearch::new(Box::new(GraphTransitionComputer::new(graph())), config(9))
+            .run(global_state(0))
+            .await;
+
+        assert!(result.path.is_none());
+        assert_eq!(result.explored_states, 5);
+    }
+
+    #[tokio::test]
+    async fn costs_saturate_instead_of_overflowing() {
+        let result = GoalSearch::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            GoalSearchConfig {
+                transition_cost: Some(Arc::new(|_| u64::MAX)),
+                heuristic: Some(Arc::new(|_| u64::MAX)),
+                ..config(3)
+            },
+        )
+        .run(global_state(0))
+        .await;
+
+        assert_eq!(result.path.unwrap().cost, u64::MAX);
+    }
+
+    #[tokio::test]
+    async fn failed_transitions_are_followed() {
+        let result = GoalSearch::new(
+            Box::new(GraphTransitionComputer::new(vec![
+                transition(0, 1, "a"),
+                failed_transition(1, 2, "b"),
+            ])),
+            config(2),
+        )
+        .run(global_state(0))
+        .await;
+
+        let path = result.path.unwrap();
+        assert_eq!(values_along(&path.transitions), vec![1, 2]);
+        assert!(!path.transitions[1].action_result.is_success());
+    }
+
+    /// Counts the states transitions are computed out of.
+    struct CountingTransitionComputer {
+        inner: GraphTransitionComputer,
+        computed: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransitionComputer for CountingTransitionComputer {
+        async fn compute(&self, from: GlobalState) -> HashSet<Transition> {
+            self.computed.fetch_add(1, Ordering::Relaxed);
+            self.inner.compute(from).await
+        }
+
+        fn action_templates(&self) -> Vec<ActionTemplate> {
+            self.inner.action_templates()
+        }
+    }
+
+    #[tokio::test]
+    async fn heuristic_keeps_the_search_away_from_hopeless_states() {
+        let computed = Arc::new(AtomicUsize::new(0));
+        let result = GoalSearch::new(
+            Box::new(CountingTransitionComputer {
+                inner: GraphTransitionComputer::new(graph()),
+                computed: computed.clone(),
+            }),
+            GoalSearchConfig {
+                heuristic: Some(Arc::new(|global_state| match value_of(global_state) {
+                    4 => 100,
+                    value => 3 - u64::from(value.min(3)),
+                })),
+                ..config(3)
+            },
+        )
+        .run(global_state(0))
+        .await;
+
+        assert_eq!(result.path.unwrap().cost, 3);
+        assert_eq!(computed.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn search_gives_up_beyond_max_states() {
+        let result = GoalSearch::new(
+            Box::new(GraphTransitionComputer::new(graph())),
+            GoalSearchConfig {
+                max_states: Some(2),
+                ..config(3)
+            },
+        )
+        .run(global_state(0))
+        .await;
+
+        assert!(result.path.is_none());
+        assert_eq!(result.explored_states, 2);
+    }
+}
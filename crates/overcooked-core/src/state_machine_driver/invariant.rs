@@ -0,0 +1,142 @@
+use crate::{
+    global_state::{GlobalState, StatePredicate},
+    state_machine_driver::trace::Trace,
+    transition::Transition,
+};
+
+/// A named predicate that must hold in every reachable [`GlobalState`].
+#[derive(Clone)]
+pub struct Invariant {
+    pub name: String,
+    pub predicate: StatePredicate,
+}
+
+/// What the driver does once an [`Invariant`] is found to be violated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViolationPolicy {
+    #[default]
+    StopAtFirstViolation,
+    Continue,
+}
+
+/// A state in which an [`Invariant`] does not hold, together with the
+/// shortest sequence of transitions leading to it from an initial state.
+#[derive(Debug, Clone)]
+pub struct InvariantViolation {
+    pub invariant: String,
+    pub state: GlobalState,
+    pub trace: Vec<Transition>,
+}
+
+impl Invariant {
+    pub fn holds(&self, global_state: &GlobalState) -> bool {
+        (self.predicate)(global_state)
+    }
+}
+
+impl InvariantViolation {
+    /// The state `trace` starts from.
+    pub fn initial_state(&self) -> &GlobalState {
+        self.trace
+            .first()
+            .map_or(&self.state, |transition| &transition.from)
+    }
+}
+
+/// Records a violation for every invariant that does not hold in
+/// `global_state` and tells whether any of them was violated. Violations that
+/// have been recorded before are not recorded again.
+pub fn check_invariants(
+    invariants: &[Invariant],
+    global_state: &GlobalState,
+    trace: &Trace,
+    violations: &mut Vec<InvariantViolation>,
+) -> bool {
+    let mut violated = false;
+    for invariant in invariants.iter() {
+        if invariant.holds(global_state) {
+            continue;
+        }
+
+        violated = true;
+        if !violations.iter().any(|violation| {
+            violation.invariant == invariant.name && violation.state == *global_state
+        }) {
+            violations.push(InvariantViolation {
+                invariant: invariant.name.clone(),
+                state: global_state.clone(),
+                trace: trace.to_vec(),
+            });
+        }
+    }
+
+    violated
+}
+
+impl std::fmt::Debug for Invariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Invariant")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        state_machine_driver::trace::Trace,
+        test_utils::test_graph::{global_state, transition, value_of},
+    };
+
+    use super::{Invariant, check_invariants};
+
+    #[test]
+    fn invariant_is_evaluated_against_global_state() {
+        let invariant = Invariant {
+            name: "value is below 3".to_string(),
+            predicate: Arc::new(|global_state| value_of(global_state) < 3),
+        };
+
+        assert!(invariant.holds(&global_state(2)));
+        assert!(!invariant.holds(&global_state(3)));
+    }
+
+    #[test]
+    fn violations_are_recorded_once_per_invariant_and_state() {
+        let invariants = vec![invariant_value_below(3), invariant_value_below(4)];
+        let trace = Trace::default().extend(transition(0, 3, "a"));
+        let mut violations = vec![];
+
+        assert!(!check_invariants(
+            &invariants,
+            &global_state(2),
+            &trace,
+            &mut violations
+        ));
+        assert!(check_invariants(
+            &invariants,
+            &global_state(3),
+            &trace,
+            &mut violations
+        ));
+        assert!(check_invariants(
+            &invariants,
+            &global_state(3),
+            &trace,
+            &mut violations
+        ));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].invariant, "value is below 3");
+        assert_eq!(violations[0].trace, trace.to_vec());
+    }
+
+    fn invariant_value_below(bound: u8) -> Invariant {
+        Invariant {
+            name: format!("value is below {bound}"),
+            predicate: Arc::new(move |global_state| value_of(global_state) < bound),
+        }
+    }
+}
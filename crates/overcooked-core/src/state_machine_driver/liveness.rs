@@ -0,0 +1,297 @@
+use std::collections::HashSet;
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::liveness::{
+        buchi_automaton::BuchiAutomaton,
+        fairness::FairnessConstraint,
+        ltl::Formula,
+        product_graph::{ProductGraph, StateGraph},
+    },
+    transition::Transition,
+};
+
+pub use ltl::Ltl;
+
+mod buchi_automaton;
+pub mod fairness;
+mod ltl;
+mod product_graph;
+
+/// A named [`Ltl`] formula that must hold on every infinite run from the
+/// initial states.
+#[derive(Debug, Clone)]
+pub struct LivenessProperty {
+    pub name: String,
+    pub formula: Ltl,
+}
+
+/// A run on which a [`LivenessProperty`] does not hold: `prefix` leads from
+/// an initial state to the first state of `cycle`, which then repeats
+/// forever.
+#[derive(Debug, Clone)]
+pub struct LivenessViolation {
+    pub property: String,
+    pub prefix: Vec<Transition>,
+    pub cycle: Vec<Transition>,
+}
+
+/// Checks every property against the state machine made of `transitions`
+/// by looking for a fair accepting cycle in its product with the Büchi
+/// automaton of the negated property.
+///
+/// Only infinite runs are considered. Runs ending in a state without out going
+/// transitions are left to deadlock detection. When `transitions` is only part
+/// of the state machine, every violation found is still a real one, but some
+/// may be missed.
+pub fn check_liveness(
+    properties: &[LivenessProperty],
+    fairness_constraints: &[FairnessConstraint],
+    initial_states: &[GlobalState],
+    transitions: &HashSet<Transition>,
+) -> Vec<LivenessViolation> {
+    if properties.is_empty() {
+        return vec![];
+    }
+
+    let graph = StateGraph::new(transitions);
+    let initial_states: Vec<usize> = initial_states
+        .iter()
+        .filter_map(|initial_state| graph.index_of(initial_state))
+        .collect();
+    if initial_states.is_empty() {
+        return vec![];
+    }
+
+    properties
+        .iter()
+        .filter_map(|property| {
+            let mut atoms = vec![];
+            let negation = Formula::from_ltl(&property.formula, true, &mut atoms);
+            let automaton = BuchiAutomaton::new(&negation);
+            let lasso = ProductGraph::new(
+                &graph,
+                &automaton,
+                &atoms,
+                fairness_constraints,
+                &initial_states,
+            )
+            .find_accepting_lasso()?;
+
+            Some(LivenessViolation {
+                property: property.name.clone(),
+                prefix: lasso.prefix.into_iter().cloned().collect(),
+                cycle: lasso.cycle.into_iter().cloned().collect(),
+            })
+        })
+        .collect()
+}
+
+impl LivenessViolation {
+    /// The state the run starts from.
+    pub fn initial_state(&self) -> &GlobalState {
+        &self
+            .prefix
+            .first()
+            .or(self.cycle.first())
+            .expect("a cycle is made of at least one transition")
+            .from
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        test_utils::test_graph::{global_state, transition, value_of},
+        transition::Transition,
+    };
+
+    use super::{
+        LivenessProperty, Ltl, check_liveness,
+        fairness::{FairActions, FairnessConstraint, FairnessKind},
+    };
+
+    /// ```text
+    ///    ┌──────┐ b ┌──────┐ c ┌──────┐
+    ///    │ GS_0 ├───► GS_1 ├───► GS_2 │
+    ///    └┬──▲──┘   └──────┘   └┬──▲──┘
+    ///     └──┘a                 └──┘d
+    /// ```
+    fn graph() -> HashSet<Transition> {
+        HashSet::from([
+            transition(0, 0, "a"),
+            transition(0, 1, "b"),
+            transition(1, 2, "c"),
+            transition(2, 2, "d"),
+        ])
+    }
+
+    fn reaches_two() -> LivenessProperty {
+        LivenessProperty {
+            name: "eventually 2".to_string(),
+            formula: Ltl::eventually(Ltl::state("2", |global_state| value_of(global_state) == 2)),
+        }
+    }
+
+    #[test]
+    fn violation_is_reported_as_lasso() {
+        let violations = check_liveness(&[reaches_two()], &[], &[global_state(0)], &graph());
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].property, "eventually 2");
+        assert!(violations[0].prefix.is_empty());
+        assert_eq!(violations[0].cycle, vec![transition(0, 0, "a")]);
+    }
+
+    #[test]
+    fn violation_is_found_from_any_initial_state() {
+        let violations = check_liveness(
+            &[reaches_two()],
+            &[],
+            &[global_state(2), global_state(0)],
+            &graph(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].initial_state(), &global_state(0));
+    }
+
+    #[test]
+    fn property_holding_on_every_run_is_not_reported() {
+        let graph = HashSet::from_iter(
+            graph()
+                .into_iter()
+                .filter(|transition| transition.action_template.label != "a"),
+        );
+
+        assert!(check_liveness(&[reaches_two()], &[], &[global_state(0)], &graph).is_empty());
+    }
+
+    #[test]
+    fn actions_can_be_referred_to() {
+        let property = LivenessProperty {
+            name: "d forever".to_string(),
+            formula: Ltl::eventually(Ltl::always(Ltl::action("d"))),
+        };
+
+        let violations = check_liveness(&[property], &[], &[global_state(0)], &graph());
+
+        assert_eq!(violations.len(), 1);
+        assert!(!violations[0].cycle.is_empty());
+        assert!(
+            violations[0]
+                .cycle
+                .iter()
+                .all(|transition| *transition == self::transition(0, 0, "a"))
+        );
+    }
+
+    /// ```text
+    ///         tock
+    ///    ┌──────◄─────┐
+    ///    │            │
+    /// ┌──▼───┐ tick ┌─┴────┐
+    /// │ GS_0 ├──────► GS_3 │
+    /// └──┬───┘      └──────┘
+    ///   b│
+    /// ┌──▼───┐ c ┌──────┐
+    /// │ GS_1 ├───► GS_2 │
+    /// └──────┘   └┬──▲──┘
+    ///             └──┘d
+    /// ```
+    fn graph_with_intermittent_action() -> HashSet<Transition> {
+        HashSet::from([
+            transition(0, 3, "tick"),
+            transition(3, 0, "tock"),
+            transition(0, 1, "b"),
+            transition(1, 2, "c"),
+            transition(2, 2, "d"),
+        ])
+    }
+
+    fn fairness(kind: FairnessKind, label: &str) -> FairnessConstraint {
+        FairnessConstraint {
+            kind,
+            actions: FairActions::Label(label.to_string()),
+        }
+    }
+
+    #[test]
+    fn weak_fairness_discards_cycles_ignoring_a_continuously_enabled_action() {
+        assert!(
+            check_liveness(
+                &[reaches_two()],
+                &[fairness(FairnessKind::Weak, "b")],
+                &[global_state(0)],
+                &graph()
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn weak_fairness_keeps_cycles_on_which_the_action_is_disabled_at_times() {
+        let violations = check_liveness(
+            &[reaches_two()],
+            &[fairness(FairnessKind::Weak, "b")],
+            &[global_state(0)],
+            &graph_with_intermittent_action(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        assert!(
+            violations[0]
+                .cycle
+                .iter()
+                .any(|transition| transition.to == global_state(3))
+        );
+    }
+
+    #[test]
+    fn strong_fairness_discards_cycles_ignoring_an_intermittently_enabled_action() {
+        assert!(
+            check_liveness(
+                &[reaches_two()],
+                &[fairness(FairnessKind::Strong, "b")],
+                &[global_state(0)],
+                &graph_with_intermittent_action(),
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn fair_counterexample_takes_the_fair_actions() {
+        let property = LivenessProperty {
+            name: "eventually d".to_string(),
+            formula: Ltl::eventually(Ltl::action("d")),
+        };
+
+        let violations = check_liveness(
+            &[property],
+            &[
+                fairness(FairnessKind::Strong, "tick"),
+                fairness(FairnessKind::Strong, "tock"),
+            ],
+            &[global_state(0)],
+            &graph_with_intermittent_action(),
+        );
+
+        assert_eq!(violations.len(), 1);
+        let labels = violations[0]
+            .cycle
+            .iter()
+            .map(|transition| transition.action_template.label.as_str())
+            .collect::<HashSet<_>>();
+        assert_eq!(labels, HashSet::from(["tick", "tock"]));
+    }
+
+    #[test]
+    fn nothing_is_checked_without_transitions() {
+        assert!(
+            check_liveness(&[reaches_two()], &[], &[global_state(0)], &HashSet::new()).is_empty()
+        );
+    }
+}
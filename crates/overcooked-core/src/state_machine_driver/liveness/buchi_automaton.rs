@@ -0,0 +1,358 @@
+use std::collections::BTreeSet;
+
+use crate::state_machine_driver::liveness::ltl::Formula;
+
+/// A generalized Büchi automaton accepting exactly the runs that satisfy a
+/// [`Formula`], built with the tableau construction of Gerth, Peled, Vardi and
+/// Wolper.
+///
+/// A run is read one position at a time. The automaton may be in a node while
+/// reading a position only if every literal of the node holds at it, and it
+/// accepts a run if it goes through every accepting set infinitely often.
+#[derive(Debug)]
+pub struct BuchiAutomaton {
+    pub nodes: Vec<AutomatonNode>,
+    pub initial_nodes: Vec<usize>,
+    pub accepting_sets: Vec<BTreeSet<usize>>,
+}
+
+#[derive(Debug)]
+pub struct AutomatonNode {
+    /// Atom indices paired with whether the atom has to hold or not.
+    pub literals: Vec<(usize, bool)>,
+    pub successors: Vec<usize>,
+}
+
+/// A node of the tableau while it is being expanded.
+#[derive(Clone)]
+struct PendingNode {
+    /// Nodes this one can be reached from, with `None` standing for the start.
+    incoming: BTreeSet<Option<usize>>,
+    new: BTreeSet<Formula>,
+    old: BTreeSet<Formula>,
+    next: BTreeSet<Formula>,
+}
+
+struct ExpandedNode {
+    incoming: BTreeSet<Option<usize>>,
+    old: BTreeSet<Formula>,
+    next: BTreeSet<Formula>,
+}
+
+impl BuchiAutomaton {
+    pub fn new(formula: &Formula) -> Self {
+        let expanded_nodes = expand(formula);
+
+        let nodes = expanded_nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| AutomatonNode {
+                literals: node
+                    .old
+                    .iter()
+                    .filter_map(|formula| match formula {
+                        Formula::Literal { atom, positive } => Some((*atom, *positive)),
+                        _ => None,
+                    })
+                    .collect(),
+                successors: expanded_nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, successor)| successor.incoming.contains(&Some(index)))
+                    .map(|(successor, _)| successor)
+                    .collect(),
+            })
+            .collect();
+
+        let initial_nodes = expanded_nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.incoming.contains(&None))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut untils = BTreeSet::new();
+        collect_untils(formula, &mut untils);
+        let accepting_sets = if untils.is_empty() {
+            vec![(0..expanded_nodes.len()).collect()]
+        } else {
+            untils
+                .into_iter()
+                .map(|until| {
+                    let Formula::Until(_, right) = until else {
+                        unreachable!()
+                    };
+                    expanded_nodes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, node)| !node.old.contains(until) || node.old.contains(right))
+                        .map(|(index, _)| index)
+                        .collect()
+                })
+                .collect()
+        };
+
+        Self {
+            nodes,
+            initial_nodes,
+            accepting_sets,
+        }
+    }
+}
+
+fn collect_untils<'a>(formula: &'a Formula, untils: &mut BTreeSet<&'a Formula>) {
+    match formula {
+        Formula::True | Formula::False | Formula::Literal { .. } => {}
+        Formula::Next(formula) => collect_untils(formula, untils),
+        Formula::And(left, right) | Formula::Or(left, right) | Formula::Release(left, right) => {
+            collect_untils(left, untils);
+            collect_untils(right, untils);
+        }
+        Formula::Until(left, right) => {
+            untils.insert(formula);
+            collect_untils(left, untils);
+            collect_untils(right, untils);
+        }
+    }
+}
+
+/// Expands `formula` into the nodes of its tableau.
+fn expand(formula: &Formula) -> Vec<ExpandedNode> {
+    let mut expanded_nodes: Vec<ExpandedNode> = vec![];
+    let mut pending_nodes = vec![PendingNode {
+        incoming: BTreeSet::from([None]),
+        new: BTreeSet::from([formula.clone()]),
+        old: BTreeSet::new(),
+        next: BTreeSet::new(),
+    }];
+
+    while let Some(mut node) = pending_nodes.pop() {
+        let Some(formula) = node.new.pop_first() else {
+            match expanded_nodes
+                .iter_mut()
+                .find(|expanded| expanded.old == node.old && expanded.next == node.next)
+            {
+                Some(expanded) => expanded.incoming.extend(node.incoming),
+                None => {
+                    pending_nodes.push(PendingNode {
+                        incoming: BTreeSet::from([Some(expanded_nodes.len())]),
+                        new: node.next.clone(),
+                        old: BTreeSet::new(),
+                        next: BTreeSet::new(),
+                    });
+                    expanded_nodes.push(ExpandedNode {
+                        incoming: node.incoming,
+                        old: node.old,
+                        next: node.next,
+                    });
+                }
+            }
+            continue;
+        };
+
+        if node.old.contains(&formula) {
+            pending_nodes.push(node);
+            continue;
+        }
+
+        match &formula {
+            Formula::False => {}
+            Formula::Literal { atom, positive } => {
+                if !node.old.contains(&Formula::Literal {
+                    atom: *atom,
+                    positive: !positive,
+                }) {
+                    node.old.insert(formula);
+                    pending_nodes.push(node);
+                }
+            }
+            Formula::True => {
+                node.old.insert(formula);
+                pending_nodes.push(node);
+            }
+            Formula::And(left, right) => {
+                add_new(&mut node, [left, right]);
+                node.old.insert(formula);
+                pending_nodes.push(node);
+            }
+            Formula::Next(next) => {
+                node.next.insert(next.as_ref().clone());
+                node.old.insert(formula);
+                pending_nodes.push(node);
+            }
+            Formula::Or(left, right) => {
+                let mut other = node.clone();
+                add_new(&mut node, [left]);
+                add_new(&mut other, [right]);
+                split(&mut pending_nodes, node, other, formula);
+            }
+            Formula::Until(left, right) => {
+                let mut other = node.clone();
+                add_new(&mut node, [left]);
+                node.next.insert(formula.clone());
+                add_new(&mut other, [right]);
+                split(&mut pending_nodes, node, other, formula);
+            }
+            Formula::Release(left, right) => {
+                let mut other = node.clone();
+                add_new(&mut node, [right]);
+                node.next.insert(formula.clone());
+                add_new(&mut other, [left, right]);
+                split(&mut pending_nodes, node, other, formula);
+            }
+        }
+    }
+
+    expanded_nodes
+}
+
+fn add_new<'a>(node: &mut PendingNode, formulas: impl IntoIterator<Item = &'a Box<Formula>>) {
+    for formula in formulas {
+        if !node.old.contains(formula.as_ref()) {
+            node.new.insert(formula.as_ref().clone());
+        }
+    }
+}
+
+fn split(
+    pending_nodes: &mut Vec<PendingNode>,
+    mut node: PendingNode,
+    mut other: PendingNode,
+    formula: Formula,
+) {
+    node.old.insert(formula.clone());
+    other.old.insert(formula);
+    pending_nodes.push(other);
+    pending_nodes.push(node);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::state_machine_driver::liveness::ltl::{Formula, Ltl};
+
+    use super::BuchiAutomaton;
+
+    /// Whether the automaton accepts the run that reads `prefix` once and then
+    /// `cycle` forever, where every position is given as the atoms holding
+    /// at it.
+    fn accepts(automaton: &BuchiAutomaton, prefix: &[&[usize]], cycle: &[&[usize]]) -> bool {
+        let enabled = |node: usize, position: &[usize]| {
+            automaton.nodes[node]
+                .literals
+                .iter()
+                .all(|(atom, positive)| position.contains(atom) == *positive)
+        };
+
+        let mut current: BTreeSet<usize> = automaton.initial_nodes.iter().copied().collect();
+        for position in prefix {
+            current = current
+                .into_iter()
+                .filter(|node| enabled(*node, position))
+                .flat_map(|node| automaton.nodes[node].successors.clone())
+                .collect();
+        }
+
+        // Unrolls the cycle over (node, offset) pairs and looks for a
+        // reachable cycle through every accepting set.
+        let length = cycle.len();
+        let step = |(node, offset): (usize, usize)| -> Vec<(usize, usize)> {
+            if !enabled(node, cycle[offset]) {
+                return vec![];
+            }
+            automaton.nodes[node]
+                .successors
+                .iter()
+                .map(|successor| (*successor, (offset + 1) % length))
+                .collect()
+        };
+        let reachable_from = |start: Vec<(usize, usize)>| {
+            let mut reached = BTreeSet::new();
+            let mut stack = start;
+            while let Some(pair) = stack.pop() {
+                if reached.insert(pair) {
+                    stack.extend(step(pair));
+                }
+            }
+            reached
+        };
+
+        reachable_from(current.into_iter().map(|node| (node, 0)).collect())
+            .into_iter()
+            .any(|pair| {
+                let in_cycle = reachable_from(step(pair));
+                in_cycle.contains(&pair)
+                    && automaton.accepting_sets.iter().all(|accepting_set| {
+                        in_cycle.iter().any(|other| {
+                            accepting_set.contains(&other.0)
+                                && reachable_from(step(*other)).contains(&pair)
+                        })
+                    })
+            })
+    }
+
+    fn automaton(ltl: Ltl) -> BuchiAutomaton {
+        let mut atoms = vec![];
+        BuchiAutomaton::new(&Formula::from_ltl(&ltl, false, &mut atoms))
+    }
+
+    fn p() -> Ltl {
+        Ltl::action("p")
+    }
+
+    fn q() -> Ltl {
+        Ltl::action("q")
+    }
+
+    #[test]
+    fn eventually_needs_the_atom_to_hold_at_some_point() {
+        let automaton = automaton(Ltl::eventually(p()));
+
+        assert!(accepts(&automaton, &[&[], &[0]], &[&[]]));
+        assert!(accepts(&automaton, &[], &[&[], &[0]]));
+        assert!(!accepts(&automaton, &[&[]], &[&[]]));
+    }
+
+    #[test]
+    fn always_needs_the_atom_to_hold_everywhere() {
+        let automaton = automaton(Ltl::always(p()));
+
+        assert!(accepts(&automaton, &[&[0]], &[&[0]]));
+        assert!(!accepts(&automaton, &[&[0], &[]], &[&[0]]));
+        assert!(!accepts(&automaton, &[], &[&[0], &[]]));
+    }
+
+    #[test]
+    fn infinitely_often_needs_the_atom_to_keep_coming_back() {
+        let automaton = automaton(Ltl::always(Ltl::eventually(p())));
+
+        assert!(accepts(&automaton, &[&[]], &[&[], &[0]]));
+        assert!(!accepts(&automaton, &[&[0], &[0]], &[&[]]));
+    }
+
+    #[test]
+    fn leads_to_needs_every_request_to_be_answered() {
+        let automaton = automaton(Ltl::leads_to(p(), q()));
+
+        assert!(accepts(&automaton, &[&[0], &[], &[1]], &[&[]]));
+        assert!(accepts(&automaton, &[], &[&[0], &[1]]));
+        assert!(!accepts(&automaton, &[&[0]], &[&[]]));
+        assert!(!accepts(&automaton, &[&[1]], &[&[0], &[]]));
+    }
+
+    #[test]
+    fn next_looks_at_the_following_position() {
+        let automaton = automaton(Ltl::next(p()));
+
+        assert!(accepts(&automaton, &[&[], &[0]], &[&[]]));
+        assert!(!accepts(&automaton, &[&[0], &[]], &[&[0]]));
+    }
+
+    #[test]
+    fn contradiction_accepts_nothing() {
+        let automaton = automaton(Ltl::and(p(), Ltl::not(p())));
+
+        assert!(automaton.initial_nodes.is_empty());
+    }
+}
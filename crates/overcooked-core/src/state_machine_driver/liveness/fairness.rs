@@ -0,0 +1,87 @@
+use crate::{actor, transition::Transition};
+
+/// An assumption about which actions get a chance to run, made when looking
+/// for liveness counterexamples. Cycles on which it does not hold are not
+/// reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FairnessConstraint {
+    pub kind: FairnessKind,
+    pub actions: FairActions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FairnessKind {
+    /// If the actions are continuously enabled, they are eventually taken.
+    Weak,
+    /// If the actions are enabled infinitely often, they are eventually taken.
+    Strong,
+}
+
+/// The actions a [`FairnessConstraint`] is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FairActions {
+    /// Every action with this label.
+    Label(String),
+    /// Every action performed by this actor.
+    Performer(actor::Id),
+}
+
+impl FairnessConstraint {
+    /// Whether `transition` is a successful step of one of the actions. A
+    /// state is said to enable the actions if one of its out going transitions
+    /// is such a step.
+    pub fn is_taken_by(&self, transition: &Transition) -> bool {
+        transition.action_result.is_success()
+            && match &self.actions {
+                FairActions::Label(label) => transition.action_template.label == *label,
+                FairActions::Performer(performer_id) => {
+                    transition.action_template.performer_id == *performer_id
+                }
+            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actor,
+        test_utils::test_graph::{ACTOR_ID, failed_transition, transition},
+    };
+
+    use super::{FairActions, FairnessConstraint, FairnessKind};
+
+    #[test]
+    fn actions_are_selected_by_label() {
+        let constraint = FairnessConstraint {
+            kind: FairnessKind::Weak,
+            actions: FairActions::Label("a".to_string()),
+        };
+
+        assert!(constraint.is_taken_by(&transition(0, 1, "a")));
+        assert!(!constraint.is_taken_by(&transition(0, 1, "b")));
+    }
+
+    #[test]
+    fn actions_are_selected_by_performer() {
+        let constraint = FairnessConstraint {
+            kind: FairnessKind::Strong,
+            actions: FairActions::Performer(ACTOR_ID.clone()),
+        };
+
+        let mut by_other_actor = transition(0, 1, "a");
+        by_other_actor.action_template.performer_id = actor::Id("actor_2".to_string());
+
+        assert!(constraint.is_taken_by(&transition(0, 1, "a")));
+        assert!(!constraint.is_taken_by(&by_other_actor));
+    }
+
+    #[test]
+    fn failed_actions_are_not_taken() {
+        let constraint = FairnessConstraint {
+            kind: FairnessKind::Weak,
+            actions: FairActions::Label("a".to_string()),
+        };
+
+        assert!(!constraint.is_taken_by(&failed_transition(0, 1, "a")));
+    }
+}
@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use crate::{
+    global_state::{GlobalState, StatePredicate},
+    transition::{Transition, TransitionPredicate},
+};
+
+/// A linear temporal logic formula over the infinite runs of a state machine.
+///
+/// A position of a run is a [`GlobalState`] together with the [`Transition`]
+/// taken out of it, so atomic propositions can refer to either of them.
+#[derive(Clone)]
+pub enum Ltl {
+    True,
+    False,
+    /// Holds at a position whose state satisfies the predicate.
+    State {
+        name: String,
+        predicate: StatePredicate,
+    },
+    /// Holds at a position whose transition is taken by an action with this
+    /// label.
+    Action(String),
+    /// Holds at a position whose transition satisfies the predicate, e.g.
+    /// one whose action returned a given output.
+    Transition {
+        name: String,
+        predicate: TransitionPredicate,
+    },
+    Not(Box<Ltl>),
+    And(Box<Ltl>, Box<Ltl>),
+    Or(Box<Ltl>, Box<Ltl>),
+    Next(Box<Ltl>),
+    Until(Box<Ltl>, Box<Ltl>),
+    Release(Box<Ltl>, Box<Ltl>),
+}
+
+impl Ltl {
+    pub fn state(
+        name: &str,
+        predicate: impl Fn(&GlobalState) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self::State {
+            name: name.to_string(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    pub fn action(label: &str) -> Self {
+        Self::Action(label.to_string())
+    }
+
+    pub fn transition(
+        name: &str,
+        predicate: impl Fn(&Transition) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self::Transition {
+            name: name.to_string(),
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    pub fn not(formula: Ltl) -> Self {
+        Self::Not(Box::new(formula))
+    }
+
+    pub fn and(left: Ltl, right: Ltl) -> Self {
+        Self::And(Box::new(left), Box::new(right))
+    }
+
+    pub fn or(left: Ltl, right: Ltl) -> Self {
+        Self::Or(Box::new(left), Box::new(right))
+    }
+
+    pub fn implies(left: Ltl, right: Ltl) -> Self {
+        Self::or(Self::not(left), right)
+    }
+
+    pub fn next(formula: Ltl) -> Self {
+        Self::Next(Box::new(formula))
+    }
+
+    pub fn until(left: Ltl, right: Ltl) -> Self {
+        Self::Until(Box::new(left), Box::new(right))
+    }
+
+    pub fn release(left: Ltl, right: Ltl) -> Self {
+        Self::Release(Box::new(left), Box::new(right))
+    }
+
+    pub fn eventually(formula: Ltl) -> Self {
+        Self::until(Self::True, formula)
+    }
+
+    pub fn always(formula: Ltl) -> Self {
+        Self::release(Self::False, formula)
+    }
+
+    /// Whenever `left` holds, `right` holds then or later.
+    pub fn leads_to(left: Ltl, right: Ltl) -> Self {
+        Self::always(Self::implies(left, Self::eventually(right)))
+    }
+}
+
+impl std::fmt::Debug for Ltl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ltl::True => write!(f, "true"),
+            Ltl::False => write!(f, "false"),
+            Ltl::State { name, .. } => write!(f, "{name:?}"),
+            Ltl::Action(label) => write!(f, "<{label}>"),
+            Ltl::Transition { name, .. } => write!(f, "<{name:?}>"),
+            Ltl::Not(formula) => write!(f, "!{formula:?}"),
+            Ltl::And(left, right) => write!(f, "({left:?} && {right:?})"),
+            Ltl::Or(left, right) => write!(f, "({left:?} || {right:?})"),
+            Ltl::Next(formula) => write!(f, "X {formula:?}"),
+            Ltl::Until(left, right) => write!(f, "({left:?} U {right:?})"),
+            Ltl::Release(left, right) => write!(f, "({left:?} R {right:?})"),
+        }
+    }
+}
+
+/// An atomic proposition of an [`Ltl`] formula.
+#[derive(Clone)]
+pub enum Atom {
+    State(StatePredicate),
+    Action(String),
+    Transition(TransitionPredicate),
+}
+
+impl Atom {
+    pub fn holds(&self, from: &GlobalState, transition: &Transition) -> bool {
+        match self {
+            Atom::State(predicate) => predicate(from),
+            Atom::Action(label) => transition.action_template.label == *label,
+            Atom::Transition(predicate) => predicate(transition),
+        }
+    }
+}
+
+/// An [`Ltl`] formula in negation normal form, with its atomic propositions
+/// replaced by their index in a table of [`Atom`]s.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Formula {
+    True,
+    False,
+    Literal { atom: usize, positive: bool },
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Next(Box<Formula>),
+    Until(Box<Formula>, Box<Formula>),
+    Release(Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    /// Pushes every negation of `ltl`, negated if `negate` is set, down to its
+    /// atomic propositions, which are appended to `atoms`.
+    pub fn from_ltl(ltl: &Ltl, negate: bool, atoms: &mut Vec<Atom>) -> Self {
+        let mut convert = |ltl: &Ltl, negate: bool| Box::new(Self::from_ltl(ltl, negate, atoms));
+        match (ltl, negate) {
+            (Ltl::True, false) | (Ltl::False, true) => Formula::True,
+            (Ltl::True, true) | (Ltl::False, false) => Formula::False,
+            (Ltl::State { predicate, .. }, negate) => Formula::Literal {
+                atom: atom_index(atoms, Atom::State(predicate.clone())),
+                positive: !negate,
+            },
+            (Ltl::Action(label), negate) => Formula::Literal {
+                atom: atom_index(atoms, Atom::Action(label.clone())),
+                positive: !negate,
+            },
+            (Ltl::Transition { predicate, .. }, negate) => Formula::Literal {
+                atom: atom_index(atoms, Atom::Transition(predicate.clone())),
+                positive: !negate,
+            },
+            (Ltl::Not(formula), negate) => Self::from_ltl(formula, !negate, atoms),
+            (Ltl::And(left, right), false) | (Ltl::Or(left, right), true) => {
+                Formula::And(convert(left, negate), convert(right, negate))
+            }
+            (Ltl::Or(left, right), false) | (Ltl::And(left, right), true) => {
+                Formula::Or(convert(left, negate), convert(right, negate))
+            }
+            (Ltl::Next(formula), negate) => Formula::Next(convert(formula, negate)),
+            (Ltl::Until(left, right), false) | (Ltl::Release(left, right), true) => {
+                Formula::Until(convert(left, negate), convert(right, negate))
+            }
+            (Ltl::Release(left, right), false) | (Ltl::Until(left, right), true) => {
+                Formula::Release(convert(left, negate), convert(right, negate))
+            }
+        }
+    }
+}
+
+/// Index of `atom` in `atoms`, appending it unless an identical one is there.
+fn atom_index(atoms: &mut Vec<Atom>, atom: Atom) -> usize {
+    let existing = atoms.iter().position(|existing| match (existing, &atom) {
+        (Atom::State(existing), Atom::State(predicate)) => Arc::ptr_eq(existing, predicate),
+        (Atom::Action(existing), Atom::Action(label)) => existing == label,
+        (Atom::Transition(existing), Atom::Transition(predicate)) => {
+            Arc::ptr_eq(existing, predicate)
+        }
+        _ => false,
+    });
+    existing.unwrap_or_else(|| {
+        atoms.push(atom);
+        atoms.len() - 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        action::ActionOutput,
+        test_utils::test_graph::{global_state, transition, value_of},
+    };
+
+    use super::{Atom, Formula, Ltl};
+
+    #[test]
+    fn negation_is_pushed_down_to_literals() {
+        let mut atoms = vec![];
+
+        let formula = Formula::from_ltl(
+            &Ltl::always(Ltl::or(
+                Ltl::action("a"),
+                Ltl::next(Ltl::state("even", |global_state| {
+                    value_of(global_state) % 2 == 0
+                })),
+            )),
+            true,
+            &mut atoms,
+        );
+
+        assert_eq!(
+            formula,
+            Formula::Until(
+                Box::new(Formula::True),
+                Box::new(Formula::And(
+                    Box::new(Formula::Literal {
+                        atom: 0,
+                        positive: false
+                    }),
+                    Box::new(Formula::Next(Box::new(Formula::Literal {
+                        atom: 1,
+                        positive: false
+                    })))
+                ))
+            )
+        );
+        assert_eq!(atoms.len(), 2);
+    }
+
+    #[test]
+    fn identical_action_atoms_are_shared() {
+        let mut atoms = vec![];
+
+        let formula = Formula::from_ltl(
+            &Ltl::and(Ltl::action("a"), Ltl::not(Ltl::action("a"))),
+            false,
+            &mut atoms,
+        );
+
+        assert_eq!(
+            formula,
+            Formula::And(
+                Box::new(Formula::Literal {
+                    atom: 0,
+                    positive: true
+                }),
+                Box::new(Formula::Literal {
+                    atom: 0,
+                    positive: false
+                })
+            )
+        );
+        assert_eq!(atoms.len(), 1);
+    }
+
+    #[test]
+    fn atoms_are_evaluated_against_state_and_transition() {
+        let transition = transition(1, 2, "a");
+
+        assert!(Atom::Action("a".to_string()).holds(&global_state(1), &transition));
+        assert!(!Atom::Action("b".to_string()).holds(&global_state(1), &transition));
+        assert!(
+            Atom::State(std::sync::Arc::new(
+                |global_state| value_of(global_state) == 1
+            ))
+            .holds(&global_state(1), &transition)
+        );
+        assert!(
+            Atom::Transition(std::sync::Arc::new(|transition| {
+                transition.action_output == Some(ActionOutput::new(()))
+            }))
+            .holds(&global_state(1), &transition)
+        );
+        assert!(
+            !Atom::Transition(std::sync::Arc::new(|transition| {
+                transition.action_output == Some(ActionOutput::new(1u8))
+            }))
+            .holds(&global_state(1), &transition)
+        );
+    }
+
+    #[test]
+    fn formulas_are_printed_readably() {
+        assert_eq!(
+            format!(
+                "{:?}",
+                Ltl::leads_to(Ltl::state("prepared", |_| true), Ltl::action("commit"))
+            ),
+            "(false R (!\"prepared\" || (true U <commit>)))"
+        );
+    }
+}
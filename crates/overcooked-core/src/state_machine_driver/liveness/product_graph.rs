@@ -0,0 +1,517 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::liveness::{
+        buchi_automaton::BuchiAutomaton,
+        fairness::{FairnessConstraint, FairnessKind},
+        ltl::Atom,
+    },
+    transition::Transition,
+};
+
+/// The state machine made of the transitions found by a run, with its states
+/// numbered.
+pub struct StateGraph<'a> {
+    pub states: Vec<&'a GlobalState>,
+    /// Out going transitions of every state along with the index of their
+    /// target.
+    pub out_going: Vec<Vec<(usize, &'a Transition)>>,
+    indices: HashMap<&'a GlobalState, usize>,
+}
+
+/// The synchronous product of a [`StateGraph`] and a [`BuchiAutomaton`]. Its
+/// cycles through every accepting set are the runs of the state machine that
+/// the automaton accepts.
+pub struct ProductGraph<'a> {
+    /// State and automaton node indices of every product node.
+    pub nodes: Vec<(usize, usize)>,
+    pub edges: Vec<Vec<(usize, &'a Transition)>>,
+    pub initial_nodes: Vec<usize>,
+    /// For every accepting set of the automaton, whether each product node is
+    /// in it.
+    pub accepting_sets: Vec<Vec<bool>>,
+    pub fairness_constraints: Vec<FairnessConstraint>,
+    /// For every fairness constraint, whether the state of each product node
+    /// enables its actions.
+    pub enabled: Vec<Vec<bool>>,
+}
+
+/// A run that reaches a cycle and then goes around it forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lasso<'a> {
+    pub prefix: Vec<&'a Transition>,
+    pub cycle: Vec<&'a Transition>,
+}
+
+impl<'a> StateGraph<'a> {
+    pub fn new(transitions: &'a HashSet<Transition>) -> Self {
+        let mut graph = Self {
+            states: vec![],
+            out_going: vec![],
+            indices: HashMap::new(),
+        };
+        for transition in transitions {
+            let from = graph.insert(&transition.from);
+            let to = graph.insert(&transition.to);
+            graph.out_going[from].push((to, transition));
+        }
+        graph
+    }
+
+    pub fn index_of(&self, global_state: &GlobalState) -> Option<usize> {
+        self.indices.get(global_state).copied()
+    }
+
+    fn insert(&mut self, global_state: &'a GlobalState) -> usize {
+        *self.indices.entry(global_state).or_insert_with(|| {
+            self.states.push(global_state);
+            self.out_going.push(vec![]);
+            self.states.len() - 1
+        })
+    }
+}
+
+impl<'a> ProductGraph<'a> {
+    pub fn new(
+        graph: &StateGraph<'a>,
+        automaton: &BuchiAutomaton,
+        atoms: &[Atom],
+        fairness_constraints: &[FairnessConstraint],
+        initial_states: &[usize],
+    ) -> Self {
+        let mut product = Self {
+            nodes: vec![],
+            edges: vec![],
+            initial_nodes: vec![],
+            accepting_sets: vec![],
+            fairness_constraints: fairness_constraints.to_vec(),
+            enabled: vec![],
+        };
+        let mut indices = HashMap::new();
+        let mut queue = VecDeque::new();
+        let mut insert = |product: &mut Self, node: (usize, usize), queue: &mut VecDeque<usize>| {
+            *indices.entry(node).or_insert_with(|| {
+                product.nodes.push(node);
+                product.edges.push(vec![]);
+                queue.push_back(product.nodes.len() - 1);
+                product.nodes.len() - 1
+            })
+        };
+
+        for initial_state in initial_states {
+            for automaton_node in &automaton.initial_nodes {
+                let index = insert(&mut product, (*initial_state, *automaton_node), &mut queue);
+                product.initial_nodes.push(index);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let (state, automaton_node) = product.nodes[index];
+            let node = &automaton.nodes[automaton_node];
+            for (target, transition) in &graph.out_going[state] {
+                let enabled = node.literals.iter().all(|(atom, positive)| {
+                    atoms[*atom].holds(graph.states[state], transition) == *positive
+                });
+                if !enabled {
+                    continue;
+                }
+                for successor in &node.successors {
+                    let successor = insert(&mut product, (*target, *successor), &mut queue);
+                    product.edges[index].push((successor, *transition));
+                }
+            }
+        }
+
+        product.accepting_sets = automaton
+            .accepting_sets
+            .iter()
+            .map(|accepting_set| {
+                product
+                    .nodes
+                    .iter()
+                    .map(|(_, automaton_node)| accepting_set.contains(automaton_node))
+                    .collect()
+            })
+            .collect();
+        product.enabled = fairness_constraints
+            .iter()
+            .map(|constraint| {
+                product
+                    .nodes
+                    .iter()
+                    .map(|(state, _)| {
+                        graph.out_going[*state]
+                            .iter()
+                            .any(|(_, transition)| constraint.is_taken_by(transition))
+                    })
+                    .collect()
+            })
+            .collect();
+        product
+    }
+
+    /// Finds a run of the product that goes through every accepting set
+    /// infinitely often and meets every fairness constraint.
+    ///
+    /// Components whose every cycle is unfair are skipped. Those that only
+    /// violate a strong fairness constraint are searched again without the
+    /// states enabling its actions, following Emerson and Lei.
+    pub fn find_accepting_lasso(&self) -> Option<Lasso<'a>> {
+        let mut candidates = vec![vec![true; self.nodes.len()]];
+        while let Some(within) = candidates.pop() {
+            for component in strongly_connected_components(&self.edges, &within) {
+                if !self.is_cyclic(&component)
+                    || !self
+                        .accepting_sets
+                        .iter()
+                        .all(|accepting_set| component.iter().any(|node| accepting_set[*node]))
+                {
+                    continue;
+                }
+
+                let in_component = mask(self.nodes.len(), &component);
+                let mut refined = in_component.clone();
+                let mut fair = true;
+                for (constraint, enabled) in self.fairness_constraints.iter().zip(&self.enabled) {
+                    if self.takes(&component, &in_component, constraint) {
+                        continue;
+                    }
+                    match constraint.kind {
+                        FairnessKind::Weak if component.iter().all(|node| enabled[*node]) => {
+                            fair = false;
+                            refined = vec![false; self.nodes.len()];
+                        }
+                        FairnessKind::Strong if component.iter().any(|node| enabled[*node]) => {
+                            fair = false;
+                            for node in &component {
+                                refined[*node] &= !enabled[*node];
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                if fair {
+                    return Some(self.lasso_through(&component));
+                }
+                if refined.contains(&true) {
+                    candidates.push(refined);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn is_cyclic(&self, component: &[usize]) -> bool {
+        component.len() > 1
+            || self.edges[component[0]]
+                .iter()
+                .any(|(target, _)| *target == component[0])
+    }
+
+    /// Whether an edge within `component` takes the actions of `constraint`.
+    fn takes(
+        &self,
+        component: &[usize],
+        in_component: &[bool],
+        constraint: &FairnessConstraint,
+    ) -> bool {
+        component.iter().any(|node| {
+            self.edges[*node].iter().any(|(target, transition)| {
+                in_component[*target] && constraint.is_taken_by(transition)
+            })
+        })
+    }
+
+    /// Builds a lasso that enters the fair `component` and then goes through
+    /// every accepting set within it, as well as through a step of, or a state
+    /// disabling, the actions of every fairness constraint.
+    fn lasso_through(&self, component: &[usize]) -> Lasso<'a> {
+        let everywhere = vec![true; self.nodes.len()];
+        let within = mask(self.nodes.len(), component);
+
+        let (prefix, entry) = self
+            .shortest_path(&self.initial_nodes, &everywhere, false, |node| within[node])
+            .expect("every product node is reachable from an initial one");
+
+        let mut cycle = vec![];
+        let mut current = entry;
+        let go_to =
+            |cycle: &mut Vec<&'a Transition>, from: usize, is_target: &dyn Fn(usize) -> bool| {
+                let (path, reached) = self
+                    .shortest_path(&[from], &within, false, is_target)
+                    .expect("the component is strongly connected");
+                cycle.extend(path);
+                reached
+            };
+
+        for accepting_set in &self.accepting_sets {
+            current = go_to(&mut cycle, current, &|node| accepting_set[node]);
+        }
+        for (constraint, enabled) in self.fairness_constraints.iter().zip(&self.enabled) {
+            let step_of = |node: usize| {
+                self.edges[node].iter().find(|(target, transition)| {
+                    within[*target] && constraint.is_taken_by(transition)
+                })
+            };
+            if self.takes(component, &within, constraint) {
+                current = go_to(&mut cycle, current, &|node| step_of(node).is_some());
+                let (target, transition) = step_of(current).unwrap();
+                cycle.push(*transition);
+                current = *target;
+            } else if constraint.kind == FairnessKind::Weak {
+                current = go_to(&mut cycle, current, &|node| !enabled[node]);
+            }
+        }
+
+        let (path, _) = self
+            .shortest_path(&[current], &within, cycle.is_empty(), |node| node == entry)
+            .expect("the component is strongly connected");
+        cycle.extend(path);
+
+        Lasso { prefix, cycle }
+    }
+
+    /// Breadth first search from `sources` to the closest node that satisfies
+    /// `is_target`, only going through nodes `within`. A path with at least one
+    /// transition is looked for if `non_empty` is set.
+    fn shortest_path(
+        &self,
+        sources: &[usize],
+        within: &[bool],
+        non_empty: bool,
+        is_target: impl Fn(usize) -> bool,
+    ) -> Option<(Vec<&'a Transition>, usize)> {
+        let mut parents: HashMap<usize, Option<(usize, &'a Transition)>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if non_empty {
+                for (target, transition) in &self.edges[*source] {
+                    if within[*target] && !parents.contains_key(target) {
+                        parents.insert(*target, Some((*source, *transition)));
+                        queue.push_back(*target);
+                    }
+                }
+            } else if !parents.contains_key(source) {
+                parents.insert(*source, None);
+                queue.push_back(*source);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            if is_target(node) {
+                let mut path = vec![];
+                let mut current = node;
+                // Sources reached again have a parent as well, which is only
+                // followed while the path is still empty.
+                while path.is_empty() || !sources.contains(&current) {
+                    let Some(Some((parent, transition))) = parents.get(&current) else {
+                        break;
+                    };
+                    path.push(*transition);
+                    current = *parent;
+                }
+                path.reverse();
+                return Some((path, node));
+            }
+            for (target, transition) in &self.edges[node] {
+                if within[*target] && !parents.contains_key(target) {
+                    parents.insert(*target, Some((node, *transition)));
+                    queue.push_back(*target);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn mask(length: usize, nodes: &[usize]) -> Vec<bool> {
+    let mut mask = vec![false; length];
+    for node in nodes {
+        mask[*node] = true;
+    }
+    mask
+}
+
+/// Tarjan's algorithm restricted to the nodes `within`, without recursion so
+/// that deep graphs do not overflow the stack.
+pub fn strongly_connected_components<T>(
+    edges: &[Vec<(usize, T)>],
+    within: &[bool],
+) -> Vec<Vec<usize>> {
+    let mut indices = vec![usize::MAX; edges.len()];
+    let mut low_links = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+    let mut next_index = 0;
+
+    for root in 0..edges.len() {
+        if !within[root] || indices[root] != usize::MAX {
+            continue;
+        }
+
+        let mut call_stack = vec![(root, 0)];
+        indices[root] = next_index;
+        low_links[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, edge)) = call_stack.last().copied() {
+            if let Some((successor, _)) = edges[node].get(edge) {
+                call_stack.last_mut().unwrap().1 += 1;
+                let successor = *successor;
+                if !within[successor] {
+                    continue;
+                }
+                if indices[successor] == usize::MAX {
+                    indices[successor] = next_index;
+                    low_links[successor] = next_index;
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack[successor] = true;
+                    call_stack.push((successor, 0));
+                } else if on_stack[successor] {
+                    low_links[node] = low_links[node].min(indices[successor]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some((parent, _)) = call_stack.last() {
+                low_links[*parent] = low_links[*parent].min(low_links[node]);
+            }
+            if low_links[node] == indices[node] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use crate::{
+        state_machine_driver::liveness::{
+            buchi_automaton::BuchiAutomaton,
+            ltl::{Formula, Ltl},
+        },
+        test_utils::test_graph::{global_state, transition},
+    };
+
+    use super::{ProductGraph, StateGraph, strongly_connected_components};
+
+    #[test]
+    fn strongly_connected_components_are_found() {
+        // 0 → 1 → 2 → 0, 2 → 3, 3 → 3, 4 → 0
+        let edges = vec![
+            vec![(1, ())],
+            vec![(2, ())],
+            vec![(0, ()), (3, ())],
+            vec![(3, ())],
+            vec![(0, ())],
+        ];
+
+        let components = strongly_connected_components(&edges, &[true; 5])
+            .into_iter()
+            .map(BTreeSet::from_iter)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            components,
+            HashSet::from([
+                BTreeSet::from([0, 1, 2]),
+                BTreeSet::from([3]),
+                BTreeSet::from([4])
+            ])
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_ignore_nodes_outside() {
+        let edges = vec![vec![(1, ())], vec![(2, ())], vec![(0, ())]];
+
+        let components = strongly_connected_components(&edges, &[true, false, true]);
+
+        assert_eq!(components.len(), 2);
+        assert!(components.iter().all(|component| component.len() == 1));
+    }
+
+    #[test]
+    fn lasso_is_found_for_a_violated_property() {
+        // 0 → 1 → 2 → 1, where "b" only leads from 0 to 1.
+        let transitions = HashSet::from([
+            transition(0, 1, "b"),
+            transition(1, 2, "a"),
+            transition(2, 1, "a"),
+        ]);
+        let graph = StateGraph::new(&transitions);
+        let mut atoms = vec![];
+        let automaton = BuchiAutomaton::new(&Formula::from_ltl(
+            &Ltl::always(Ltl::eventually(Ltl::action("b"))),
+            true,
+            &mut atoms,
+        ));
+
+        let lasso = ProductGraph::new(
+            &graph,
+            &automaton,
+            &atoms,
+            &[],
+            &[graph.index_of(&global_state(0)).unwrap()],
+        )
+        .find_accepting_lasso()
+        .unwrap();
+
+        let cycle_start = &lasso.cycle.first().unwrap().from;
+        assert_eq!(&lasso.cycle.last().unwrap().to, cycle_start);
+        assert!(
+            lasso
+                .cycle
+                .iter()
+                .all(|transition| transition.action_template.label == "a")
+        );
+        assert_eq!(lasso.prefix.first().unwrap().from, global_state(0));
+        assert_eq!(&lasso.prefix.last().unwrap().to, cycle_start);
+    }
+
+    #[test]
+    fn no_lasso_is_found_for_a_property_that_holds() {
+        let transitions = HashSet::from([transition(0, 1, "a"), transition(1, 0, "b")]);
+        let graph = StateGraph::new(&transitions);
+        let mut atoms = vec![];
+        let automaton = BuchiAutomaton::new(&Formula::from_ltl(
+            &Ltl::always(Ltl::eventually(Ltl::action("b"))),
+            true,
+            &mut atoms,
+        ));
+
+        assert!(
+            ProductGraph::new(
+                &graph,
+                &automaton,
+                &atoms,
+                &[],
+                &[graph.index_of(&global_state(0)).unwrap()]
+            )
+            .find_accepting_lasso()
+            .is_none()
+        );
+    }
+}
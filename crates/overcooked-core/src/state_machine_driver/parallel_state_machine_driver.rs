@@ -0,0 +1,404 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    mem,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::{
+        TransitionComputer,
+        action_fault::{ActionFault, check_action_faults},
+        concurrent_visited_set::ConcurrentVisitedSet,
+        exploration_budget::Termination,
+        exploration_result::ExplorationResult,
+        invariant::{Invariant, InvariantViolation, ViolationPolicy, check_invariants},
+        search_strategy::SearchNode,
+        trace::Trace,
+    },
+    transition::Transition,
+};
+
+/// Explores the state space with several workers running on the tokio
+/// runtime at the same time.
+///
+/// Every worker owns a frontier it pushes newly discovered states to and pops
+/// them from; a worker that runs out of states steals the oldest ones from
+/// the other workers. Workers share a single visited set, so every state is
+/// still explored exactly once and the transitions found are the same as
+/// the ones found by [`StateMachineDriver`](super::StateMachineDriver). What
+/// else they find is kept by every worker on its own and merged once they are
+/// all done.
+///
+/// Traces reported for violations and action faults are valid but not
+/// necessarily the shortest ones.
+pub struct ParallelStateMachineDriver {
+    transition_computer: Arc<dyn TransitionComputer>,
+    config: ParallelExplorationConfig,
+    worker_count: usize,
+}
+
+/// Settings of a single [`ParallelStateMachineDriver`] run, which only checks
+/// invariants and action faults.
+#[derive(Debug, Clone, Default)]
+pub struct ParallelExplorationConfig {
+    /// Checked on every newly discovered state, including the initial one.
+    pub invariants: Vec<Invariant>,
+    pub violation_policy: ViolationPolicy,
+}
+
+/// State of a single run shared by all of its workers.
+struct Exploration {
+    transition_computer: Arc<dyn TransitionComputer>,
+    invariants: Vec<Invariant>,
+    violation_policy: ViolationPolicy,
+    visited: ConcurrentVisitedSet,
+    frontiers: Vec<Mutex<VecDeque<SearchNode>>>,
+    /// Number of discovered states that have not been fully explored yet.
+    pending: AtomicUsize,
+    stopped: AtomicBool,
+    notify: Notify,
+}
+
+/// What a single worker has found.
+#[derive(Default)]
+struct Findings {
+    transitions: HashSet<Transition>,
+    violations: Vec<InvariantViolation>,
+    action_faults: Vec<ActionFault>,
+}
+
+impl ParallelStateMachineDriver {
+    pub fn new(
+        transition_computer: Arc<dyn TransitionComputer>,
+        config: ParallelExplorationConfig,
+        worker_count: usize,
+    ) -> Self {
+        Self {
+            transition_computer,
+            config,
+            worker_count: worker_count.max(1),
+        }
+    }
+
+    pub async fn run(&self, initial_state: GlobalState) -> ExplorationResult {
+        self.run_from_all([initial_state]).await
+    }
+
+    /// Explores the states reachable from any of `initial_states`, which are
+    /// handed out to the workers in turn.
+    pub async fn run_from_all(
+        &self,
+        initial_states: impl IntoIterator<Item = GlobalState>,
+    ) -> ExplorationResult {
+        let exploration = Arc::new(Exploration {
+            transition_computer: self.transition_computer.clone(),
+            invariants: self.config.invariants.clone(),
+            violation_policy: self.config.violation_policy,
+            visited: ConcurrentVisitedSet::new(self.worker_count * 4),
+            frontiers: (0..self.worker_count)
+                .map(|_| Mutex::new(VecDeque::new()))
+                .collect(),
+            pending: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+            notify: Notify::new(),
+        });
+
+        let mut findings = Findings::default();
+        for (index, initial_state) in initial_states.into_iter().enumerate() {
+            exploration.discover(
+                index % self.worker_count,
+                SearchNode {
+                    state: initial_state,
+                    trace: Trace::default(),
+                },
+                &mut findings,
+            );
+        }
+
+        let workers = (0..self.worker_count)
+            .map(|worker| {
+                let exploration = exploration.clone();
+                tokio::spawn(async move { exploration.work(worker).await })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            findings.merge(worker.await.expect("exploration worker panicked"));
+        }
+
+        let exploration = Arc::into_inner(exploration)
+            .expect("all workers have finished, so nothing else refers to the exploration");
+        let termination = if exploration.stopped.into_inner() {
+            Termination::ViolationFound
+        } else {
+            Termination::Completed
+        };
+        ExplorationResult {
+            transition_count: findings.transitions.len(),
+            transitions: findings.transitions,
+            visited_states: exploration.visited.into_states(),
+            violations: findings.violations,
+            action_faults: findings.action_faults,
+            termination,
+            ..ExplorationResult::default()
+        }
+    }
+}
+
+impl Findings {
+    fn merge(&mut self, mut other: Findings) {
+        if other.transitions.len() > self.transitions.len() {
+            mem::swap(&mut self.transitions, &mut other.transitions);
+        }
+        self.transitions.extend(other.transitions);
+        self.violations.append(&mut other.violations);
+        self.action_faults.append(&mut other.action_faults);
+    }
+}
+
+impl Exploration {
+    async fn work(&self, worker: usize) -> Findings {
+        let mut findings = Findings::default();
+        loop {
+            // Created before looking for work so that no notification sent in
+            // between can be missed.
+            let notified = self.notify.notified();
+
+            if self.stopped.load(Ordering::Acquire) {
+                return findings;
+            }
+
+            match self.take(worker) {
+                Some(node) => {
+                    self.expand(worker, node, &mut findings).await;
+                    if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+                        self.notify.notify_waiters();
+                    }
+                }
+                None if self.pending.load(Ordering::Acquire) == 0 => return findings,
+                None => notified.await,
+            }
+        }
+    }
+
+    /// Pops from the worker's own frontier, or steals from another one.
+    fn take(&self, worker: usize) -> Option<SearchNode> {
+        if let Some(node) = self.frontiers[worker].lock().unwrap().pop_back() {
+            return Some(node);
+        }
+
+        (1..self.frontiers.len())
+            .map(|offset| (worker + offset) % self.frontiers.len())
+            .find_map(|victim| self.frontiers[victim].lock().unwrap().pop_front())
+    }
+
+    async fn expand(&self, worker: usize, node: SearchNode, findings: &mut Findings) {
+        let out_going_transitions = self.transition_computer.compute(node.state).await;
+        findings
+            .transitions
+            .extend(out_going_transitions.iter().cloned());
+        if self.check_action_faults(&node.trace, &out_going_transitions, findings) {
+            return;
+        }
+
+        for transition in out_going_transitions {
+            if self.stopped.load(Ordering::Acquire) {
+                return;
+            }
+
+            self.discover(
+                worker,
+                SearchNode {
+                    state: transition.to.clone(),
+                    trace: node.trace.extend(transition),
+                },
+                findings,
+            );
+        }
+    }
+
+    /// Records the faults among `out_going_transitions` and tells whether the
+    /// exploration has to stop because of them.
+    fn check_action_faults(
+        &self,
+        trace: &Trace,
+        out_going_transitions: &HashSet<Transition>,
+        findings: &mut Findings,
+    ) -> bool {
+        let mut action_faults = Vec::new();
+        let faulted = check_action_faults(out_going_transitions, trace, &mut action_faults);
+        self.record(faulted, action_faults, &mut findings.action_faults)
+    }
+
+    fn discover(&self, worker: usize, node: SearchNode, findings: &mut Findings) {
+        if !self.visited.insert(node.state.clone()) {
+            return;
+        }
+
+        let mut violations = Vec::new();
+        let violated =
+            check_invariants(&self.invariants, &node.state, &node.trace, &mut violations);
+        if self.record(violated, violations, &mut findings.violations) {
+            return;
+        }
+
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        self.frontiers[worker].lock().unwrap().push_back(node);
+        self.notify.notify_one();
+    }
+
+    /// Adds what was just `found` to what a worker has found, and tells
+    /// whether the exploration has to stop because of it. When stopping at
+    /// the first violation, only the worker stopping the exploration gets to
+    /// record anything, so that no other violation or fault is reported.
+    fn record<T>(&self, violated: bool, mut found: Vec<T>, recorded: &mut Vec<T>) -> bool {
+        if self.violation_policy == ViolationPolicy::Continue {
+            recorded.append(&mut found);
+            return false;
+        }
+        if !violated {
+            return self.stopped.load(Ordering::Acquire);
+        }
+
+        if !self.stopped.swap(true, Ordering::AcqRel) {
+            recorded.append(&mut found);
+            self.notify.notify_waiters();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use test_case::test_case;
+
+    use crate::{
+        state_machine_driver::{
+            StateMachineDriver,
+            exploration_budget::Termination,
+            exploration_config::ExplorationConfig,
+            invariant::{Invariant, ViolationPolicy},
+        },
+        test_utils::test_graph::{GraphTransitionComputer, global_state, transition, value_of},
+        transition::Transition,
+    };
+
+    use super::{ParallelExplorationConfig, ParallelStateMachineDriver};
+
+    const STATE_COUNT: u8 = 60;
+
+    #[test_case(1)]
+    #[test_case(2)]
+    #[test_case(8)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn explores_the_same_transitions_as_the_sequential_driver(worker_count: usize) {
+        let sequential_result = StateMachineDriver::new(
+            Box::new(graph_transition_computer()),
+            ExplorationConfig::default(),
+        )
+        .run(global_state(0))
+        .await;
+
+        let parallel_result = ParallelStateMachineDriver::new(
+            Arc::new(graph_transition_computer()),
+            ParallelExplorationConfig::default(),
+            worker_count,
+        )
+        .run(global_state(0))
+        .await;
+
+        assert_eq!(parallel_result.transitions, HashSet::from_iter(graph()));
+        assert_eq!(parallel_result.transitions, sequential_result.transitions);
+        assert_eq!(
+            parallel_result.visited_states,
+            sequential_result.visited_states
+        );
+        assert!(parallel_result.violations.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn stops_at_first_violation_with_a_valid_trace() {
+        let result = ParallelStateMachineDriver::new(
+            Arc::new(graph_transition_computer()),
+            ParallelExplorationConfig {
+                invariants: vec![invariant_value_below(STATE_COUNT / 2)],
+                ..ParallelExplorationConfig::default()
+            },
+            4,
+        )
+        .run(global_state(0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.violations.len(), 1);
+        let violation = &result.violations[0];
+        assert!(value_of(&violation.state) >= STATE_COUNT / 2);
+        assert_eq!(violation.trace.first().unwrap().from, global_state(0));
+        assert_eq!(violation.trace.last().unwrap().to, violation.state);
+        assert!(
+            violation
+                .trace
+                .windows(2)
+                .all(|pair| pair[0].to == pair[1].from)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn reports_every_violation_when_configured_to_continue() {
+        let result = ParallelStateMachineDriver::new(
+            Arc::new(graph_transition_computer()),
+            ParallelExplorationConfig {
+                invariants: vec![invariant_value_below(STATE_COUNT / 2)],
+                violation_policy: ViolationPolicy::Continue,
+            },
+            4,
+        )
+        .run(global_state(0))
+        .await;
+
+        assert_eq!(
+            result
+                .violations
+                .iter()
+                .map(|violation| value_of(&violation.state))
+                .collect::<HashSet<_>>(),
+            HashSet::from_iter(STATE_COUNT / 2..STATE_COUNT)
+        );
+        assert_eq!(result.transitions, HashSet::from_iter(graph()));
+    }
+
+    fn graph_transition_computer() -> GraphTransitionComputer {
+        GraphTransitionComputer::new(graph())
+    }
+
+    /// Every state leads to its successor and to seven times its value,
+    /// both modulo [`STATE_COUNT`].
+    fn graph() -> Vec<Transition> {
+        (0..STATE_COUNT)
+            .flat_map(|value| {
+                [
+                    transition(value, (value + 1) % STATE_COUNT, "increase"),
+                    transition(
+                        value,
+                        ((value as u16 * 7) % STATE_COUNT as u16) as u8,
+                        "multiply",
+                    ),
+                ]
+            })
+            .collect()
+    }
+
+    fn invariant_value_below(bound: u8) -> Invariant {
+        Invariant {
+            name: format!("value is below {bound}"),
+            predicate: Arc::new(move |global_state| value_of(global_state) < bound),
+        }
+    }
+}
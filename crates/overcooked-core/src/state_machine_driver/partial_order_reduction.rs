@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use crate::{action::ActionTemplate, actor, global_state::GlobalState, transition::Transition};
+
+/// Explores a subset of the out going transitions of a state, an ample set,
+/// whenever the transitions left out are independent of it, rather than every
+/// interleaving of actions that commute.
+///
+/// Two actions are independent when the actors they involve, i.e. their
+/// performer and receivers, are disjoint. Actions yielding no transition, such
+/// as disabled ones or failed ones that are dropped, are taken into account all
+/// the same: an action left out of the ample set could otherwise enable one of
+/// them that depends on it. This relies on guards and outcomes only depending
+/// on the performer and receivers of their action, which
+/// [`ActionGuard`](crate::action::ActionGuard) ensures for guards.
+#[derive(Debug, Clone)]
+pub struct PartialOrderReduction {
+    /// Actors whose states the invariants look at. Actions involving them are
+    /// only explored along with every other one, so an invariant reading an
+    /// actor left out of it may miss the interleavings it is violated on.
+    /// Liveness properties are checked on every interleaving instead.
+    pub visible_actors: HashSet<actor::Id>,
+    /// Labels of actions with effects beyond their performer and receiver,
+    /// such as state shared behind their back. They are treated as dependent
+    /// on every other action.
+    pub dependent_labels: HashSet<String>,
+}
+
+impl PartialOrderReduction {
+    /// Reduction with no action depending on everything.
+    pub fn new(visible_actors: HashSet<actor::Id>) -> Self {
+        Self {
+            visible_actors,
+            dependent_labels: HashSet::new(),
+        }
+    }
+
+    /// Picks the smallest set of `transitions` that is closed under dependency
+    /// and only involves invisible actions, the dependencies running through
+    /// every one of `action_templates` whether it yields a transition or not.
+    /// Every transition is picked if no such set exists, or if one of its
+    /// transitions leads to a state for which `is_visited` holds, so that no
+    /// action is postponed around a cycle forever.
+    pub fn ample_set<'a>(
+        &self,
+        action_templates: &[ActionTemplate],
+        transitions: &'a HashSet<Transition>,
+        is_visited: impl Fn(&GlobalState) -> bool,
+    ) -> Vec<&'a Transition> {
+        let mut seeds: Vec<&Transition> = transitions.iter().collect();
+        seeds.sort_by(|left, right| sort_key(left).cmp(&sort_key(right)));
+
+        let mut tried = HashSet::new();
+        let mut smallest: Option<Vec<&Transition>> = None;
+        for seed in seeds {
+            if !tried.insert(&seed.action_template) {
+                continue;
+            }
+
+            let ample_set = self.dependency_closure(action_templates, transitions, seed);
+            if ample_set.len() == transitions.len()
+                || smallest
+                    .as_ref()
+                    .is_some_and(|smallest| smallest.len() <= ample_set.len())
+                || ample_set
+                    .iter()
+                    .any(|transition| self.is_visible(transition) || is_visited(&transition.to))
+            {
+                continue;
+            }
+            smallest = Some(ample_set);
+        }
+
+        smallest.unwrap_or_else(|| transitions.iter().collect())
+    }
+
+    /// Transitions of every action that `seed` depends on, directly or not.
+    fn dependency_closure<'a>(
+        &self,
+        action_templates: &[ActionTemplate],
+        transitions: &'a HashSet<Transition>,
+        seed: &'a Transition,
+    ) -> Vec<&'a Transition> {
+        if self.is_dependent_on_everything(&seed.action_template) {
+            return transitions.iter().collect();
+        }
+
+        let mut actor_ids: HashSet<&actor::Id> =
+            seed.action_template.actor_ids().into_iter().collect();
+        loop {
+            let mut grown = false;
+            for action_template in action_templates.iter().chain(
+                transitions
+                    .iter()
+                    .map(|transition| &transition.action_template),
+            ) {
+                let involved = action_template.actor_ids();
+                if !involved.iter().any(|actor_id| actor_ids.contains(actor_id)) {
+                    continue;
+                }
+                if self.is_dependent_on_everything(action_template) {
+                    return transitions.iter().collect();
+                }
+                for actor_id in involved {
+                    grown |= actor_ids.insert(actor_id);
+                }
+            }
+            if !grown {
+                break;
+            }
+        }
+
+        transitions
+            .iter()
+            .filter(|transition| {
+                transition
+                    .action_template
+                    .actor_ids()
+                    .iter()
+                    .any(|actor_id| actor_ids.contains(actor_id))
+            })
+            .collect()
+    }
+
+    fn is_dependent_on_everything(&self, action_template: &ActionTemplate) -> bool {
+        self.dependent_labels.contains(&action_template.label)
+    }
+
+    fn is_visible(&self, transition: &Transition) -> bool {
+        transition
+            .action_template
+            .actor_ids()
+            .iter()
+            .any(|actor_id| self.visible_actors.contains(*actor_id))
+    }
+}
+
+fn sort_key(transition: &Transition) -> (&actor::Id, &str, Vec<&actor::Id>) {
+    let action_template = &transition.action_template;
+    (
+        &action_template.performer_id,
+        &action_template.label,
+        action_template.receiver_ids(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashSet},
+        sync::{Arc, LazyLock},
+    };
+
+    use crate::{
+        action::{ActionOutput, ActionResult, ActionTemplate, ActionType},
+        actor::{self, local_state::LocalState},
+        global_state::GlobalState,
+        test_utils::{test_actors::TestActor1State, test_graph},
+        transition::Transition,
+    };
+
+    use super::PartialOrderReduction;
+
+    static ACTOR_1: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_1".to_string()));
+    static ACTOR_2: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_2".to_string()));
+    static ACTOR_3: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_3".to_string()));
+
+    fn global_state(values: [u8; 3]) -> GlobalState {
+        GlobalState::new(BTreeMap::from_iter(
+            [&*ACTOR_1, &*ACTOR_2, &*ACTOR_3]
+                .into_iter()
+                .zip(values)
+                .map(|(actor_id, value)| {
+                    (
+                        actor_id.clone(),
+                        LocalState {
+                            actor_state: Arc::new(TestActor1State { value }),
+                        },
+                    )
+                }),
+        ))
+    }
+
+    fn transition(
+        performer_id: &actor::Id,
+        receiver_id: Option<&actor::Id>,
+        label: &str,
+        to: [u8; 3],
+    ) -> Transition {
+        let action_type = match receiver_id {
+            None => test_graph::action(label).action_type,
+            Some(receiver_id) => ActionType::Transitive {
+                receiver_id: receiver_id.clone(),
+                action: Arc::new(|_, _| Box::pin(async { Ok(ActionOutput::new(())) })),
+            },
+        };
+        Transition {
+            from: global_state([0, 0, 0]),
+            to: global_state(to),
+            action_template: ActionTemplate::new(
+                performer_id.clone(),
+                label.to_string(),
+                action_type,
+            ),
+            action_result: ActionResult::Succeeded,
+            action_output: Some(ActionOutput::new(())),
+        }
+    }
+
+    /// `a` on actor 1, `b` from actor 2 to actor 3 and `c` on actor 3.
+    fn transitions() -> HashSet<Transition> {
+        HashSet::from([
+            transition(&ACTOR_1, None, "a", [1, 0, 0]),
+            transition(&ACTOR_2, Some(&ACTOR_3), "b", [0, 1, 1]),
+            transition(&ACTOR_3, None, "c", [0, 0, 1]),
+        ])
+    }
+
+    fn action_templates(transitions: &HashSet<Transition>) -> Vec<ActionTemplate> {
+        transitions
+            .iter()
+            .map(|transition| transition.action_template.clone())
+            .collect()
+    }
+
+    fn labels(ample_set: Vec<&Transition>) -> HashSet<&str> {
+        ample_set
+            .into_iter()
+            .map(|transition| transition.action_template.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn smallest_independent_set_is_picked() {
+        let transitions = transitions();
+
+        let ample_set = PartialOrderReduction::new(HashSet::new()).ample_set(
+            &action_templates(&transitions),
+            &transitions,
+            |_| false,
+        );
+
+        assert_eq!(labels(ample_set), HashSet::from(["a"]));
+    }
+
+    #[test]
+    fn actions_sharing_an_actor_are_kept_together() {
+        let transitions = transitions();
+        let por = PartialOrderReduction::new(HashSet::from([ACTOR_1.clone()]));
+
+        let ample_set = por.ample_set(&action_templates(&transitions), &transitions, |_| false);
+
+        assert_eq!(labels(ample_set), HashSet::from(["b", "c"]));
+    }
+
+    #[test]
+    fn every_transition_is_picked_when_all_are_visible() {
+        let transitions = transitions();
+        let por = PartialOrderReduction::new(HashSet::from([ACTOR_1.clone(), ACTOR_3.clone()]));
+
+        assert_eq!(
+            por.ample_set(&action_templates(&transitions), &transitions, |_| false)
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn actions_with_hidden_effects_depend_on_everything() {
+        let transitions = transitions();
+        let por = PartialOrderReduction {
+            dependent_labels: HashSet::from(["a".to_string()]),
+            ..PartialOrderReduction::new(HashSet::new())
+        };
+
+        let ample_set = por.ample_set(&action_templates(&transitions), &transitions, |_| false);
+
+        assert_eq!(labels(ample_set), HashSet::from(["b", "c"]));
+    }
+
+    #[test]
+    fn actions_yielding_no_transition_still_depend_on_others() {
+        let transitions = transitions();
+        let mut action_templates = action_templates(&transitions);
+        action_templates.push(ActionTemplate {
+            guard: Some(Arc::new(|_, _| false)),
+            ..transition(&ACTOR_1, Some(&ACTOR_2), "d", [0, 0, 0]).action_template
+        });
+
+        let ample_set = PartialOrderReduction::new(HashSet::new()).ample_set(
+            &action_templates,
+            &transitions,
+            |_| false,
+        );
+
+        assert_eq!(labels(ample_set), HashSet::from(["a", "b", "c"]));
+    }
+
+    #[test]
+    fn ample_set_closing_a_cycle_is_not_picked() {
+        let transitions = transitions();
+        let visited = global_state([1, 0, 0]);
+
+        let ample_set = PartialOrderReduction::new(HashSet::new()).ample_set(
+            &action_templates(&transitions),
+            &transitions,
+            |state| *state == visited,
+        );
+
+        assert_eq!(labels(ample_set), HashSet::from(["b", "c"]));
+    }
+}
@@ -0,0 +1,99 @@
+use std::io;
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::{
+        disk_spill::{DiskSpill, SpillCodecs},
+        search_strategy::{
+            breadth_first_search::BreadthFirstSearch, depth_first_search::DepthFirstSearch,
+            disk_breadth_first_search::DiskBreadthFirstSearch,
+            iterative_deepening_search::IterativeDeepeningSearch,
+            random_priority_search::RandomPrioritySearch,
+        },
+        trace::Trace,
+    },
+};
+
+mod breadth_first_search;
+mod depth_first_search;
+mod disk_breadth_first_search;
+mod iterative_deepening_search;
+mod random_priority_search;
+
+/// A discovered state waiting to be explored.
+#[derive(Debug, Clone)]
+pub struct SearchNode {
+    pub state: GlobalState,
+    pub trace: Trace,
+}
+
+/// Decides the order in which discovered states are explored.
+pub trait SearchStrategy: Send {
+    fn push(&mut self, node: SearchNode);
+    fn pop(&mut self) -> Option<SearchNode>;
+
+    /// The nodes waiting to be popped, in an order in which pushing them into
+    /// a new search strategy makes it pop them in the same order.
+    fn queued_nodes(&self) -> Vec<SearchNode>;
+
+    /// Whether nodes are popped by increasing depth.
+    fn explores_by_depth(&self) -> bool {
+        false
+    }
+
+    /// Whether a visited state has to be explored again once it is reached
+    /// through a shorter trace than the one it was first discovered with.
+    fn revisits_shallower_states(&self) -> bool {
+        false
+    }
+
+    /// Called once there is nothing left to pop. Returning `true` makes the
+    /// driver forget the visited states and start over from the initial state.
+    fn start_next_round(&mut self) -> bool {
+        false
+    }
+
+    /// The first error met while moving queued nodes to disk or reading them
+    /// back, since which nodes may have been lost.
+    fn take_error(&mut self) -> Option<io::Error> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum SearchStrategyType {
+    #[default]
+    BreadthFirst,
+    /// Breadth first, with the queue spilled to disk past a memory threshold.
+    DiskBreadthFirst {
+        disk_spill: DiskSpill,
+        codecs: SpillCodecs,
+    },
+    DepthFirst,
+    IterativeDeepening,
+    RandomPriority {
+        seed: u64,
+    },
+}
+
+impl SearchNode {
+    pub fn depth(&self) -> usize {
+        self.trace.len()
+    }
+}
+
+impl SearchStrategyType {
+    pub fn create(&self) -> Box<dyn SearchStrategy> {
+        match self {
+            SearchStrategyType::BreadthFirst => Box::new(BreadthFirstSearch::default()),
+            SearchStrategyType::DiskBreadthFirst { disk_spill, codecs } => Box::new(
+                DiskBreadthFirstSearch::new(disk_spill.clone(), codecs.clone()),
+            ),
+            SearchStrategyType::DepthFirst => Box::new(DepthFirstSearch::default()),
+            SearchStrategyType::IterativeDeepening => Box::new(IterativeDeepeningSearch::default()),
+            SearchStrategyType::RandomPriority { seed } => {
+                Box::new(RandomPrioritySearch::new(*seed))
+            }
+        }
+    }
+}
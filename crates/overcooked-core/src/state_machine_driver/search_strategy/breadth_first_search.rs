@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+
+use crate::state_machine_driver::search_strategy::{SearchNode, SearchStrategy};
+
+/// Explores states in the order they are discovered, which yields the
+/// shortest trace to every state.
+#[derive(Default)]
+pub struct BreadthFirstSearch {
+    queue: VecDeque<SearchNode>,
+}
+
+impl SearchStrategy for BreadthFirstSearch {
+    fn push(&mut self, node: SearchNode) {
+        self.queue.push_back(node);
+    }
+
+    fn pop(&mut self) -> Option<SearchNode> {
+        self.queue.pop_front()
+    }
+
+    fn queued_nodes(&self) -> Vec<SearchNode> {
+        self.queue.iter().cloned().collect()
+    }
+
+    fn explores_by_depth(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_machine_driver::{
+            search_strategy::{SearchNode, SearchStrategy},
+            trace::Trace,
+        },
+        test_utils::test_graph::{global_state, value_of},
+    };
+
+    use super::BreadthFirstSearch;
+
+    #[test]
+    fn nodes_are_popped_in_fifo_order() {
+        let mut search = BreadthFirstSearch::default();
+        for value in 0..3 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default(),
+            });
+        }
+
+        assert_eq!(value_of(&search.pop().unwrap().state), 0);
+        assert_eq!(value_of(&search.pop().unwrap().state), 1);
+        assert_eq!(value_of(&search.pop().unwrap().state), 2);
+        assert!(search.pop().is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::state_machine_driver::search_strategy::{SearchNode, SearchStrategy};
+
+/// Explores the most recently discovered state first. Only the states on
+/// the frontier of the current branch are kept, which makes it a good fit
+/// for finding deep bugs with little memory.
+#[derive(Default)]
+pub struct DepthFirstSearch {
+    stack: Vec<SearchNode>,
+}
+
+impl SearchStrategy for DepthFirstSearch {
+    fn push(&mut self, node: SearchNode) {
+        self.stack.push(node);
+    }
+
+    fn pop(&mut self) -> Option<SearchNode> {
+        self.stack.pop()
+    }
+
+    fn queued_nodes(&self) -> Vec<SearchNode> {
+        self.stack.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_machine_driver::{
+            search_strategy::{SearchNode, SearchStrategy},
+            trace::Trace,
+        },
+        test_utils::test_graph::{global_state, value_of},
+    };
+
+    use super::DepthFirstSearch;
+
+    #[test]
+    fn nodes_are_popped_in_lifo_order() {
+        let mut search = DepthFirstSearch::default();
+        for value in 0..3 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default(),
+            });
+        }
+
+        assert_eq!(value_of(&search.pop().unwrap().state), 2);
+        assert_eq!(value_of(&search.pop().unwrap().state), 1);
+        assert_eq!(value_of(&search.pop().unwrap().state), 0);
+        assert!(search.pop().is_none());
+    }
+
+    #[test]
+    fn queued_nodes_are_pushed_again_in_the_same_order() {
+        let mut search = DepthFirstSearch::default();
+        for value in 0..3 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default(),
+            });
+        }
+        let mut restored = DepthFirstSearch::default();
+        for node in search.queued_nodes() {
+            restored.push(node);
+        }
+
+        for _ in 0..3 {
+            assert_eq!(
+                value_of(&restored.pop().unwrap().state),
+                value_of(&search.pop().unwrap().state)
+            );
+        }
+    }
+}
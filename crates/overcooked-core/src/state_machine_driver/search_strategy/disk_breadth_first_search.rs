@@ -0,0 +1,282 @@
+use std::{cell::OnceCell, collections::VecDeque, fs, io, mem, path::PathBuf};
+
+use crate::state_machine_driver::{
+    disk_spill::{
+        DiskSpill, SpillCodecs, SpillDirectory,
+        node_codec::{Decoder, NodeCodec},
+    },
+    search_strategy::{SearchNode, SearchStrategy},
+};
+
+/// Same as [`BreadthFirstSearch`](super::breadth_first_search::BreadthFirstSearch),
+/// except that queued nodes are kept encoded and written to files once they
+/// take more than the memory threshold, so about twice the threshold is used
+/// in memory however long the queue gets.
+///
+/// Once the spilled nodes cannot be written or read back, nothing more is
+/// popped and the error is kept for [`SearchStrategy::take_error`].
+pub struct DiskBreadthFirstSearch {
+    disk_spill: DiskSpill,
+    codec: NodeCodec,
+    /// Only created once nodes are spilled.
+    directory: Option<SpillDirectory>,
+    /// Encoded nodes being popped, the oldest ones of the queue.
+    head: Vec<u8>,
+    head_position: usize,
+    /// Files of encoded nodes pushed after the ones of `head`, oldest first.
+    segments: VecDeque<PathBuf>,
+    segment_count: u64,
+    /// Encoded nodes pushed after the ones of `segments`.
+    tail: Vec<u8>,
+    /// The first spilling error, set from listing the queued nodes as well.
+    error: OnceCell<io::Error>,
+}
+
+impl DiskBreadthFirstSearch {
+    pub fn new(disk_spill: DiskSpill, codecs: SpillCodecs) -> Self {
+        Self {
+            codec: NodeCodec::new(disk_spill.clone(), codecs),
+            disk_spill,
+            directory: None,
+            head: Vec::new(),
+            head_position: 0,
+            segments: VecDeque::new(),
+            segment_count: 0,
+            tail: Vec::new(),
+            error: OnceCell::new(),
+        }
+    }
+
+    fn spill_tail(&mut self) -> io::Result<()> {
+        if self.directory.is_none() {
+            self.directory = Some(self.disk_spill.create_directory("frontier")?);
+        }
+        let segment = self
+            .directory
+            .as_ref()
+            .unwrap()
+            .file(format!("segment-{}", self.segment_count));
+        self.segment_count += 1;
+
+        fs::write(&segment, &self.tail)?;
+        self.segments.push_back(segment);
+        self.tail.clear();
+        Ok(())
+    }
+
+    /// Moves the oldest nodes not popped yet into `head`.
+    fn refill_head(&mut self) -> io::Result<()> {
+        self.head_position = 0;
+        self.head = match self.segments.pop_front() {
+            Some(segment) => {
+                let bytes = fs::read(&segment);
+                let _ = fs::remove_file(&segment);
+                bytes?
+            }
+            None => mem::take(&mut self.tail),
+        };
+        Ok(())
+    }
+
+    fn try_pop(&mut self) -> io::Result<Option<SearchNode>> {
+        if self.head_position == self.head.len() {
+            self.refill_head()?;
+        }
+        if self.head.is_empty() {
+            return Ok(None);
+        }
+
+        let mut decoder = Decoder::new(&self.head[self.head_position..]);
+        let node = self.codec.decode(&mut decoder)?;
+        self.head_position = self.head.len() - decoder.remaining();
+        Ok(Some(node))
+    }
+
+    fn try_queued_nodes(&self) -> io::Result<Vec<SearchNode>> {
+        let mut nodes = Vec::new();
+        let mut decode_all = |bytes: &[u8]| {
+            let mut decoder = Decoder::new(bytes);
+            while !decoder.is_empty() {
+                nodes.push(self.codec.peek(&mut decoder)?);
+            }
+            io::Result::Ok(())
+        };
+        decode_all(&self.head[self.head_position..])?;
+        for segment in &self.segments {
+            decode_all(&fs::read(segment)?)?;
+        }
+        decode_all(&self.tail)?;
+        Ok(nodes)
+    }
+
+    fn keep<T: Default>(&self, error: io::Error) -> T {
+        let _ = self.error.set(error);
+        T::default()
+    }
+}
+
+impl SearchStrategy for DiskBreadthFirstSearch {
+    fn push(&mut self, node: SearchNode) {
+        let pushed = self.codec.encode(&node, &mut self.tail).and_then(|_| {
+            if self.tail.len() > self.disk_spill.memory_threshold {
+                self.spill_tail()
+            } else {
+                Ok(())
+            }
+        });
+        if let Err(error) = pushed {
+            self.keep(error)
+        }
+    }
+
+    fn pop(&mut self) -> Option<SearchNode> {
+        if self.error.get().is_some() {
+            return None;
+        }
+        self.try_pop().unwrap_or_else(|error| self.keep(error))
+    }
+
+    fn queued_nodes(&self) -> Vec<SearchNode> {
+        self.try_queued_nodes()
+            .unwrap_or_else(|error| self.keep(error))
+    }
+
+    fn explores_by_depth(&self) -> bool {
+        true
+    }
+
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, sync::Arc};
+
+    use test_case::test_case;
+
+    use crate::{
+        state_machine_driver::{
+            disk_spill::{DiskSpill, SpillCodecs},
+            search_strategy::{SearchNode, SearchStrategy},
+            trace::Trace,
+        },
+        test_utils::test_graph::{
+            TestActor1StateCodec, TestOutputCodec, global_state, transition, value_of,
+        },
+    };
+
+    use super::DiskBreadthFirstSearch;
+
+    fn search(memory_threshold: usize) -> DiskBreadthFirstSearch {
+        search_in(DiskSpill {
+            directory: env::temp_dir(),
+            memory_threshold,
+        })
+    }
+
+    fn search_in(disk_spill: DiskSpill) -> DiskBreadthFirstSearch {
+        DiskBreadthFirstSearch::new(
+            disk_spill,
+            SpillCodecs {
+                codec: Arc::new(TestActor1StateCodec),
+                action_output_codec: Arc::new(TestOutputCodec),
+            },
+        )
+    }
+
+    #[test_case(0 ; "every node spilled")]
+    #[test_case(64 ; "some nodes spilled")]
+    #[test_case(usize::MAX ; "nothing spilled")]
+    fn nodes_are_popped_in_fifo_order(memory_threshold: usize) {
+        let mut search = search(memory_threshold);
+        for value in 0..5 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default().extend(transition(0, value, "a")),
+            });
+        }
+        assert_eq!(value_of(&search.pop().unwrap().state), 0);
+        assert_eq!(value_of(&search.pop().unwrap().state), 1);
+
+        search.push(SearchNode {
+            state: global_state(5),
+            trace: Trace::default(),
+        });
+        for value in 2..6 {
+            assert_eq!(value_of(&search.pop().unwrap().state), value);
+        }
+        assert!(search.pop().is_none());
+    }
+
+    #[test]
+    fn queued_nodes_include_the_spilled_ones() {
+        let mut search = search(16);
+        for value in 0..5 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default(),
+            });
+        }
+        search.pop();
+
+        assert!(!search.segments.is_empty());
+        assert_eq!(
+            search
+                .queued_nodes()
+                .iter()
+                .map(|node| value_of(&node.state))
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn traces_survive_spilling() {
+        let mut search = search(0);
+        let trace = Trace::default()
+            .extend(transition(0, 1, "a"))
+            .extend(transition(1, 2, "b"));
+        search.push(SearchNode {
+            state: global_state(2),
+            trace: trace.clone(),
+        });
+
+        assert_eq!(search.segments.len(), 1);
+        assert_eq!(search.pop().unwrap().trace.to_vec(), trace.to_vec());
+    }
+
+    #[test]
+    fn spilled_nodes_are_removed_with_the_search() {
+        let mut search = search(0);
+        search.push(SearchNode {
+            state: global_state(0),
+            trace: Trace::default(),
+        });
+        let directory = search.directory.as_ref().unwrap().path().to_path_buf();
+
+        assert!(directory.exists());
+        drop(search);
+        assert!(!directory.exists());
+    }
+
+    #[test]
+    fn spilling_errors_stop_the_search_and_are_kept() {
+        let file = env::temp_dir().join(format!("not-a-directory-frontier-{}", std::process::id()));
+        fs::write(&file, []).unwrap();
+        let mut search = search_in(DiskSpill {
+            directory: file.clone(),
+            memory_threshold: 0,
+        });
+        search.push(SearchNode {
+            state: global_state(0),
+            trace: Trace::default(),
+        });
+
+        assert!(search.pop().is_none());
+        assert!(search.take_error().is_some());
+        assert!(search.take_error().is_none());
+        fs::remove_file(file).unwrap();
+    }
+}
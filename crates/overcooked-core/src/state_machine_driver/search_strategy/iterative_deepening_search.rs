@@ -0,0 +1,90 @@
+use crate::state_machine_driver::search_strategy::{SearchNode, SearchStrategy};
+
+/// Depth first search bounded by a depth limit that grows by one every round.
+///
+/// Each round starts over from the initial state, so the first round that
+/// reaches a state does so through a shortest trace, without holding a whole
+/// breadth first frontier in memory.
+#[derive(Default)]
+pub struct IterativeDeepeningSearch {
+    stack: Vec<SearchNode>,
+    depth_limit: usize,
+    cut_off: bool,
+}
+
+impl SearchStrategy for IterativeDeepeningSearch {
+    fn push(&mut self, node: SearchNode) {
+        if node.depth() > self.depth_limit {
+            self.cut_off = true;
+        } else {
+            self.stack.push(node);
+        }
+    }
+
+    fn pop(&mut self) -> Option<SearchNode> {
+        self.stack.pop()
+    }
+
+    fn queued_nodes(&self) -> Vec<SearchNode> {
+        self.stack.clone()
+    }
+
+    fn revisits_shallower_states(&self) -> bool {
+        true
+    }
+
+    fn start_next_round(&mut self) -> bool {
+        if !self.cut_off {
+            return false;
+        }
+
+        self.cut_off = false;
+        self.depth_limit += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_machine_driver::{
+            search_strategy::{SearchNode, SearchStrategy},
+            trace::Trace,
+        },
+        test_utils::test_graph::{global_state, transition, value_of},
+    };
+
+    use super::IterativeDeepeningSearch;
+
+    #[test]
+    fn nodes_beyond_depth_limit_are_cut_off() {
+        let mut search = IterativeDeepeningSearch::default();
+        search.push(SearchNode {
+            state: global_state(0),
+            trace: Trace::default(),
+        });
+        search.push(node_at_depth_one());
+
+        assert_eq!(value_of(&search.pop().unwrap().state), 0);
+        assert!(search.pop().is_none());
+    }
+
+    #[test]
+    fn next_round_is_started_with_a_deeper_limit_only_after_a_cut_off() {
+        let mut search = IterativeDeepeningSearch::default();
+        search.push(node_at_depth_one());
+
+        assert!(search.start_next_round());
+
+        search.push(node_at_depth_one());
+        assert_eq!(value_of(&search.pop().unwrap().state), 1);
+        assert!(!search.start_next_round());
+    }
+
+    fn node_at_depth_one() -> SearchNode {
+        SearchNode {
+            state: global_state(1),
+            trace: Trace::default().extend(transition(0, 1, "a")),
+        }
+    }
+}
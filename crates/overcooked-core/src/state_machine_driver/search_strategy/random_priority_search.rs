@@ -0,0 +1,117 @@
+use std::collections::BinaryHeap;
+
+use crate::{
+    rng::SplitMix64,
+    state_machine_driver::search_strategy::{SearchNode, SearchStrategy},
+};
+
+/// Explores states in an order given by priorities drawn from a seeded
+/// random number generator. Useful to shake out bugs that the systematic
+/// orders keep reaching late.
+pub struct RandomPrioritySearch {
+    rng: SplitMix64,
+    heap: BinaryHeap<PrioritizedNode>,
+}
+
+struct PrioritizedNode {
+    priority: u64,
+    node: SearchNode,
+}
+
+impl RandomPrioritySearch {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SplitMix64::new(seed),
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl SearchStrategy for RandomPrioritySearch {
+    fn push(&mut self, node: SearchNode) {
+        self.heap.push(PrioritizedNode {
+            priority: self.rng.next_u64(),
+            node,
+        });
+    }
+
+    fn pop(&mut self) -> Option<SearchNode> {
+        self.heap.pop().map(|prioritized| prioritized.node)
+    }
+
+    /// The nodes get new priorities once pushed again, so their order does
+    /// not matter.
+    fn queued_nodes(&self) -> Vec<SearchNode> {
+        self.heap
+            .iter()
+            .map(|prioritized| prioritized.node.clone())
+            .collect()
+    }
+}
+
+impl PartialEq for PrioritizedNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for PrioritizedNode {}
+
+impl PartialOrd for PrioritizedNode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PrioritizedNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        state_machine_driver::{
+            search_strategy::{SearchNode, SearchStrategy},
+            trace::Trace,
+        },
+        test_utils::test_graph::{global_state, value_of},
+    };
+
+    use super::RandomPrioritySearch;
+
+    #[test]
+    fn every_node_is_popped_once() {
+        let popped = pop_all(RandomPrioritySearch::new(0));
+
+        assert_eq!(HashSet::<u8>::from_iter(popped), HashSet::from_iter(0..10));
+    }
+
+    #[test]
+    fn order_is_determined_by_seed() {
+        assert_eq!(
+            pop_all(RandomPrioritySearch::new(1)),
+            pop_all(RandomPrioritySearch::new(1))
+        );
+        assert_ne!(
+            pop_all(RandomPrioritySearch::new(1)),
+            pop_all(RandomPrioritySearch::new(2))
+        );
+    }
+
+    fn pop_all(mut search: RandomPrioritySearch) -> Vec<u8> {
+        for value in 0..10 {
+            search.push(SearchNode {
+                state: global_state(value),
+                trace: Trace::default(),
+            });
+        }
+
+        std::iter::from_fn(|| search.pop())
+            .map(|node| value_of(&node.state))
+            .collect()
+    }
+}
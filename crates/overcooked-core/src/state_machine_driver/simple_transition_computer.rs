@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{
     ActionTemplateExecutor,
-    action::ActionTemplate,
+    action::{ActionResult, ActionTemplate, ExecutionConfig, FailedActionPolicy},
     actor::{self, actor_factory::ActorFactory, actor_state_extractor::ActorStateExtractor},
     create_executor,
     global_state::GlobalState,
@@ -11,8 +11,11 @@ use crate::{
 };
 
 pub struct SimpleTransitionComputer {
+    /// Parameterized actions are expanded into one action per argument.
     actions: HashSet<ActionTemplate>,
-    action_template_executor: Box<dyn ActionTemplateExecutor + Sync>,
+    action_template_executor: Box<dyn ActionTemplateExecutor + Send + Sync>,
+    /// Applies to the actions that do not set their own.
+    failed_action_policy: FailedActionPolicy,
 }
 
 impl SimpleTransitionComputer {
@@ -20,10 +23,16 @@ impl SimpleTransitionComputer {
         actions: HashSet<ActionTemplate>,
         actor_factories: HashMap<actor::Id, Box<dyn ActorFactory>>,
         actor_state_extractors: HashMap<actor::Id, Box<dyn ActorStateExtractor>>,
+        config: ExecutionConfig,
     ) -> Self {
         Self {
-            actions,
-            action_template_executor: create_executor(actor_factories, actor_state_extractors),
+            actions: actions.iter().flat_map(ActionTemplate::expand).collect(),
+            action_template_executor: create_executor(
+                actor_factories,
+                actor_state_extractors,
+                &config,
+            ),
+            failed_action_policy: config.failed_action_policy,
         }
     }
 }
@@ -31,22 +40,63 @@ impl SimpleTransitionComputer {
 #[async_trait::async_trait]
 impl TransitionComputer for SimpleTransitionComputer {
     async fn compute(&self, from: GlobalState) -> HashSet<Transition> {
+        self.compute_with_dropped(from).await.0
+    }
+
+    async fn compute_with_dropped(
+        &self,
+        from: GlobalState,
+    ) -> (HashSet<Transition>, Vec<Transition>) {
         let mut transitions = HashSet::new();
+        let mut dropped_failures = Vec::new();
 
         for action_template in self.actions.iter() {
-            let result = self
+            if !action_template.is_enabled(&from) {
+                continue;
+            }
+            let results = self
                 .action_template_executor
                 .execute(action_template.clone(), from.clone())
                 .await;
-            transitions.insert(Transition {
-                from: from.clone(),
-                to: result.global_states.clone(),
-                action_template: action_template.clone(),
-                action_result: result.action_result,
-            });
+            for result in results {
+                let mut dropped = false;
+                let to = match &result.action_result {
+                    ActionResult::Succeeded => result.global_states,
+                    ActionResult::Failed(_) => match action_template
+                        .failed_action_policy
+                        .unwrap_or(self.failed_action_policy)
+                    {
+                        FailedActionPolicy::KeepState => result.global_states,
+                        FailedActionPolicy::RollBack => from.clone(),
+                        FailedActionPolicy::Drop => {
+                            dropped = true;
+                            result.global_states
+                        }
+                    },
+                    // The actors may have been left halfway through the
+                    // action, and the fault is to be reported anyway.
+                    ActionResult::Panicked(_) | ActionResult::TimedOut(_) => from.clone(),
+                };
+                let transition = Transition {
+                    from: from.clone(),
+                    to,
+                    action_template: action_template.clone(),
+                    action_result: result.action_result,
+                    action_output: result.action_output,
+                };
+                if dropped {
+                    dropped_failures.push(transition);
+                } else {
+                    transitions.insert(transition);
+                }
+            }
         }
 
-        transitions
+        (transitions, dropped_failures)
+    }
+
+    fn action_templates(&self) -> Vec<ActionTemplate> {
+        self.actions.iter().cloned().collect()
     }
 }
 
@@ -56,18 +106,25 @@ mod tests {
         collections::{BTreeMap, HashSet},
         error::Error,
         sync::{Arc, LazyLock},
+        time::Duration,
     };
 
     use mockall::predicate::eq;
+    use test_case::test_case;
 
     use crate::{
         action::{
-            ActionResult, ActionTemplate, ActionType, ExecutionResult, MockActionTemplateExecutor,
+            ActionOutput, ActionResult, ActionTemplate, ActionType, ExecutionResult,
+            FailedActionPolicy, MockActionTemplateExecutor,
         },
         actor::{self, ActorBase, actor_state::ActorState, local_state::LocalState},
         global_state::GlobalState,
-        state_machine_driver::TransitionComputer,
-        test_utils::test_actors::{TestActor1State, TestActor2State},
+        state_machine_driver::{
+            StateMachineDriver, TransitionComputer, exploration_budget::Termination,
+            exploration_config::ExplorationConfig, invariant::Invariant,
+            partial_order_reduction::PartialOrderReduction,
+        },
+        test_utils::test_actors::{TestActor1Error, TestActor1State, TestActor2State},
         transition::Transition,
     };
 
@@ -99,6 +156,7 @@ mod tests {
         let state_machine_driver = SimpleTransitionComputer {
             actions,
             action_template_executor: Box::new(executor),
+            failed_action_policy: FailedActionPolicy::KeepState,
         };
 
         assert_eq!(
@@ -108,18 +166,294 @@ mod tests {
                     from: global_state_0.clone(),
                     to: global_state_1,
                     action_template: action_a(),
-                    action_result: ActionResult(None)
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(())),
                 },
                 Transition {
                     from: global_state_0.clone(),
                     to: global_state_2,
                     action_template: action_b(),
-                    action_result: ActionResult(None)
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(())),
                 }
             ])
         );
     }
 
+    #[tokio::test]
+    async fn disabled_actions_are_not_executed() {
+        let global_state_0 = global_state(0, 0);
+        let global_state_1 = global_state(0, 1);
+        let mut executor = MockActionTemplateExecutor::new();
+        prepare_executor(
+            &mut executor,
+            action_a(),
+            global_state_0.clone(),
+            global_state_1.clone(),
+        );
+
+        let state_machine_driver = SimpleTransitionComputer {
+            actions: HashSet::from([
+                action_a(),
+                ActionTemplate {
+                    // Only enabled once the receiver has left its initial state.
+                    guard: Some(Arc::new(|_, receivers| {
+                        receivers[0]
+                            != LocalState {
+                                actor_state: actor_2_state(0),
+                            }
+                    })),
+                    ..action_b()
+                },
+            ]),
+            action_template_executor: Box::new(executor),
+            failed_action_policy: FailedActionPolicy::KeepState,
+        };
+
+        assert_eq!(
+            state_machine_driver.compute(global_state_0.clone()).await,
+            HashSet::from([Transition {
+                from: global_state_0,
+                to: global_state_1,
+                action_template: action_a(),
+                action_result: ActionResult::Succeeded,
+                action_output: Some(ActionOutput::new(())),
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn outcomes_reaching_the_same_state_are_told_apart_by_their_output() {
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().once().return_once(|_, _| {
+            [6u8, 7u8]
+                .map(|value| ExecutionResult {
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(value)),
+                    global_states: global_state(0, 0),
+                })
+                .into()
+        });
+
+        let transition_computer = SimpleTransitionComputer {
+            actions: HashSet::from([action_a()]),
+            action_template_executor: Box::new(executor),
+            failed_action_policy: FailedActionPolicy::KeepState,
+        };
+
+        assert_eq!(
+            transition_computer
+                .compute(global_state(0, 0))
+                .await
+                .into_iter()
+                .map(|transition| *transition
+                    .action_output
+                    .unwrap()
+                    .downcast_ref::<u8>()
+                    .unwrap())
+                .collect::<HashSet<_>>(),
+            HashSet::from([6, 7])
+        );
+    }
+
+    #[test_case(FailedActionPolicy::KeepState, None, Some(global_state(1, 0)) ; "partially mutated state kept")]
+    #[test_case(FailedActionPolicy::RollBack, None, Some(global_state(0, 0)) ; "rolled back")]
+    #[test_case(FailedActionPolicy::Drop, None, None ; "dropped")]
+    #[test_case(FailedActionPolicy::KeepState, Some(FailedActionPolicy::Drop), None ; "dropped by the action")]
+    #[test_case(FailedActionPolicy::Drop, Some(FailedActionPolicy::RollBack), Some(global_state(0, 0)) ; "rolled back by the action")]
+    #[tokio::test]
+    async fn failed_actions_follow_their_policy(
+        model_policy: FailedActionPolicy,
+        action_policy: Option<FailedActionPolicy>,
+        expected_to: Option<GlobalState>,
+    ) {
+        let action = ActionTemplate {
+            failed_action_policy: action_policy,
+            ..action_a()
+        };
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().once().return_once(|_, _| {
+            vec![ExecutionResult {
+                action_result: ActionResult::Failed(Arc::new(TestActor1Error)),
+                action_output: None,
+                global_states: global_state(1, 0),
+            }]
+        });
+
+        let transition_computer = SimpleTransitionComputer {
+            actions: HashSet::from([action]),
+            action_template_executor: Box::new(executor),
+            failed_action_policy: model_policy,
+        };
+
+        assert_eq!(
+            transition_computer
+                .compute(global_state(0, 0))
+                .await
+                .into_iter()
+                .map(|transition| transition.to)
+                .collect::<Vec<_>>(),
+            Vec::from_iter(expected_to)
+        );
+    }
+
+    #[test_case(ActionResult::Panicked("boom".to_string()) ; "panicked")]
+    #[test_case(ActionResult::TimedOut(Duration::from_secs(1)) ; "timed out")]
+    #[tokio::test]
+    async fn faulty_actions_are_rolled_back_whatever_the_policy(action_result: ActionResult) {
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().once().return_once(|_, _| {
+            vec![ExecutionResult {
+                action_result,
+                action_output: None,
+                global_states: global_state(1, 0),
+            }]
+        });
+
+        let transition_computer = SimpleTransitionComputer {
+            actions: HashSet::from([action_a()]),
+            action_template_executor: Box::new(executor),
+            failed_action_policy: FailedActionPolicy::Drop,
+        };
+
+        let transitions = transition_computer.compute(global_state(0, 0)).await;
+        assert_eq!(transitions.len(), 1);
+        let transition = transitions.into_iter().next().unwrap();
+        assert_eq!(transition.to, global_state(0, 0));
+        assert!(transition.action_result.is_fault());
+    }
+
+    #[tokio::test]
+    async fn faults_are_kept_apart_from_successful_self_loops() {
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().once().return_once(|_, _| {
+            vec![
+                ExecutionResult {
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(())),
+                    global_states: global_state(0, 0),
+                },
+                ExecutionResult {
+                    action_result: ActionResult::Panicked("boom".to_string()),
+                    action_output: None,
+                    global_states: global_state(1, 0),
+                },
+            ]
+        });
+
+        let transition_computer = SimpleTransitionComputer {
+            actions: HashSet::from([action_a()]),
+            action_template_executor: Box::new(executor),
+            failed_action_policy: FailedActionPolicy::KeepState,
+        };
+
+        let transitions = transition_computer.compute(global_state(0, 0)).await;
+        assert_eq!(transitions.len(), 2);
+        assert!(
+            transitions
+                .iter()
+                .all(|transition| transition.to == global_state(0, 0))
+        );
+        assert_eq!(
+            transitions
+                .iter()
+                .filter(|transition| transition.action_result.is_fault())
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_failures_are_still_covered() {
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().returning(|_, from| {
+            vec![ExecutionResult {
+                action_result: ActionResult::Failed(Arc::new(TestActor1Error)),
+                action_output: None,
+                global_states: from,
+            }]
+        });
+
+        let result = StateMachineDriver::new(
+            Box::new(SimpleTransitionComputer {
+                actions: HashSet::from([action_a()]),
+                action_template_executor: Box::new(executor),
+                failed_action_policy: FailedActionPolicy::Drop,
+            }),
+            ExplorationConfig {
+                coverage: true,
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(global_state(0, 0))
+        .await;
+
+        assert!(result.transitions.is_empty());
+        let coverage = result.coverage.unwrap();
+        assert_eq!(
+            coverage.always_failed().cloned().collect::<Vec<_>>(),
+            vec![action_a()]
+        );
+        assert_eq!(coverage.actions[0].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn dropped_actions_still_count_as_dependent_in_partial_order_reduction() {
+        let action_d = ActionTemplate {
+            failed_action_policy: Some(FailedActionPolicy::Drop),
+            ..action_d()
+        };
+        // `d` only succeeds once `c` has been taken before `a`.
+        let edges = [
+            (action_a(), global_state(0, 0), global_state(1, 0)),
+            (action_a(), global_state(0, 1), global_state(1, 1)),
+            (action_c(), global_state(0, 0), global_state(0, 1)),
+            (action_c(), global_state(1, 0), global_state(1, 1)),
+            (action_d.clone(), global_state(0, 1), global_state(0, 2)),
+        ];
+        let mut executor = MockActionTemplateExecutor::new();
+        executor.expect_execute().returning(move |action, from| {
+            vec![match edges
+                .iter()
+                .find(|(edge_action, edge_from, _)| *edge_action == action && *edge_from == from)
+            {
+                Some((_, _, to)) => ExecutionResult {
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(())),
+                    global_states: to.clone(),
+                },
+                None => ExecutionResult {
+                    action_result: ActionResult::Failed(Arc::new(TestActor1Error)),
+                    action_output: None,
+                    global_states: from,
+                },
+            }]
+        });
+
+        let result = StateMachineDriver::new(
+            Box::new(SimpleTransitionComputer {
+                actions: HashSet::from([action_a(), action_c(), action_d]),
+                action_template_executor: Box::new(executor),
+                failed_action_policy: FailedActionPolicy::KeepState,
+            }),
+            ExplorationConfig {
+                invariants: vec![Invariant {
+                    name: "d never succeeds".to_string(),
+                    predicate: Arc::new(|global_state_| *global_state_ != global_state(0, 2)),
+                }],
+                partial_order_reduction: Some(PartialOrderReduction::new(HashSet::from([
+                    ACTOR_2_ID.clone(),
+                ]))),
+                ..ExplorationConfig::default()
+            },
+        )
+        .run(global_state(0, 0))
+        .await;
+
+        assert_eq!(result.termination, Termination::ViolationFound);
+        assert_eq!(result.violations[0].state, global_state(0, 2));
+    }
+
     fn prepare_executor(
         executor: &mut MockActionTemplateExecutor,
         action: ActionTemplate,
@@ -130,9 +464,12 @@ mod tests {
             .expect_execute()
             .with(eq(action), eq(from))
             .once()
-            .return_once(|_, _| ExecutionResult {
-                action_result: ActionResult(None),
-                global_states: to,
+            .return_once(|_, _| {
+                vec![ExecutionResult {
+                    action_result: ActionResult::Succeeded,
+                    action_output: Some(ActionOutput::new(())),
+                    global_states: to,
+                }]
             });
     }
 
@@ -166,38 +503,61 @@ mod tests {
     }
 
     fn action_a() -> ActionTemplate {
-        ActionTemplate {
-            performer_id: ACTOR_1_ID.clone(),
-            label: "action-A".to_string(),
-            action_type: ActionType::Intransitive(Arc::new(|actor| {
+        ActionTemplate::new(
+            ACTOR_1_ID.clone(),
+            "action-A".to_string(),
+            ActionType::Intransitive(Arc::new(|actor| {
                 Box::pin(proxy_for_intransitive_action(actor))
             })),
-        }
+        )
     }
 
     fn action_b() -> ActionTemplate {
-        ActionTemplate {
-            performer_id: ACTOR_1_ID.clone(),
-            label: "action-B".to_string(),
-            action_type: ActionType::Transitive {
+        ActionTemplate::new(
+            ACTOR_1_ID.clone(),
+            "action-B".to_string(),
+            ActionType::Transitive {
                 receiver_id: ACTOR_2_ID.clone(),
                 action: Arc::new(|performer, receiver| {
                     Box::pin(proxy_for_transitive_action(performer, receiver))
                 }),
             },
-        }
+        )
+    }
+
+    fn action_c() -> ActionTemplate {
+        ActionTemplate::new(
+            ACTOR_2_ID.clone(),
+            "action-C".to_string(),
+            ActionType::Intransitive(Arc::new(|actor| {
+                Box::pin(proxy_for_intransitive_action(actor))
+            })),
+        )
+    }
+
+    fn action_d() -> ActionTemplate {
+        ActionTemplate::new(
+            ACTOR_2_ID.clone(),
+            "action-D".to_string(),
+            ActionType::Transitive {
+                receiver_id: ACTOR_1_ID.clone(),
+                action: Arc::new(|performer, receiver| {
+                    Box::pin(proxy_for_transitive_action(performer, receiver))
+                }),
+            },
+        )
     }
 
     async fn proxy_for_intransitive_action(
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 
     async fn proxy_for_transitive_action(
         _: Arc<dyn ActorBase>,
         _: Arc<dyn ActorBase>,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+        Ok(ActionOutput::new(()))
     }
 }
@@ -0,0 +1,597 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ActionTemplateExecutor,
+    action::{ActionResult, ActionTemplate, ExecutionConfig, FailedActionPolicy},
+    actor::{self, actor_factory::ActorFactory, actor_state_extractor::ActorStateExtractor},
+    create_executor,
+    global_state::GlobalState,
+    rng::SplitMix64,
+    state_machine_driver::{
+        action_fault::ActionFault,
+        deadlock::{Deadlock, DeadlockDetection},
+        invariant::{Invariant, InvariantViolation, ViolationPolicy, check_invariants},
+        trace::Trace,
+    },
+    transition::Transition,
+};
+
+/// Runs random sequences of actions from an initial state, for models whose
+/// state space is too large to be explored exhaustively.
+///
+/// At every step the actions are tried in a random order and the first one
+/// with an outcome leaving the state is taken, failed outcomes being followed
+/// as the failed action policy says. A walk ends once it reaches
+/// the configured length, when it gets stuck, when an invariant is violated,
+/// or when a tried action panics or times out.
+pub struct Simulator {
+    /// Kept in a fixed order so that a seed always leads to the same walk.
+    actions: Vec<ActionTemplate>,
+    action_template_executor: Box<dyn ActionTemplateExecutor + Send + Sync>,
+    config: SimulationConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    pub trace_length: usize,
+    pub walk_count: usize,
+    pub invariants: Vec<Invariant>,
+    pub violation_policy: ViolationPolicy,
+    /// Reports the walks getting stuck in a state that is not terminal.
+    pub deadlock_detection: Option<DeadlockDetection>,
+    /// An action timing out is reported as a fault of the walk.
+    pub execution: ExecutionConfig,
+}
+
+/// A single random walk. Running [`Simulator::walk`] with the same `seed`
+/// replays it exactly.
+#[derive(Debug, Clone)]
+pub struct SimulatedWalk {
+    pub seed: u64,
+    pub trace: Vec<Transition>,
+    pub violations: Vec<InvariantViolation>,
+    /// The action that panicked or timed out, which ended the walk.
+    pub action_fault: Option<ActionFault>,
+    /// The state the walk got stuck in, if it is not a terminal one.
+    pub deadlock: Option<Deadlock>,
+}
+
+impl SimulatedWalk {
+    pub fn failed(&self) -> bool {
+        !self.violations.is_empty() || self.action_fault.is_some() || self.deadlock.is_some()
+    }
+}
+
+/// What a step of a walk led to.
+enum Step {
+    Taken(Transition),
+    /// A tried action panicked or timed out.
+    Faulted(Transition),
+    /// Every enabled action was tried and none of them left the state.
+    Stuck,
+}
+
+#[derive(Debug, Default)]
+pub struct SimulationResult {
+    pub walk_count: usize,
+    pub failed_walks: Vec<SimulatedWalk>,
+}
+
+impl Simulator {
+    pub fn new(
+        actions: HashSet<ActionTemplate>,
+        actor_factories: HashMap<actor::Id, Box<dyn ActorFactory>>,
+        actor_state_extractors: HashMap<actor::Id, Box<dyn ActorStateExtractor>>,
+        config: SimulationConfig,
+    ) -> Self {
+        Self {
+            actions: sorted(actions),
+            action_template_executor: create_executor(
+                actor_factories,
+                actor_state_extractors,
+                &config.execution,
+            ),
+            config,
+        }
+    }
+
+    pub async fn run(&self, initial_state: GlobalState) -> SimulationResult {
+        let mut result = SimulationResult::default();
+        let mut seeds = SplitMix64::new(self.config.seed);
+
+        for _ in 0..self.config.walk_count {
+            let walk = self.walk(initial_state.clone(), seeds.next_u64()).await;
+            result.walk_count += 1;
+
+            if walk.failed() {
+                result.failed_walks.push(walk);
+                if self.config.violation_policy == ViolationPolicy::StopAtFirstViolation {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+
+    pub async fn walk(&self, initial_state: GlobalState, seed: u64) -> SimulatedWalk {
+        let mut rng = SplitMix64::new(seed);
+        let mut trace = Trace::default();
+        let mut violations = Vec::new();
+        let mut action_fault = None;
+        let mut deadlock = None;
+        let mut curr = initial_state;
+
+        check_invariants(&self.config.invariants, &curr, &trace, &mut violations);
+        while violations.is_empty() && trace.len() < self.config.trace_length {
+            let transition = match self.step(&curr, &mut rng).await {
+                Step::Taken(transition) => transition,
+                Step::Faulted(transition) => {
+                    action_fault = Some(ActionFault {
+                        transition,
+                        trace: trace.to_vec(),
+                    });
+                    break;
+                }
+                Step::Stuck => {
+                    if self
+                        .config
+                        .deadlock_detection
+                        .as_ref()
+                        .is_some_and(|detection| !detection.is_terminal(&curr))
+                    {
+                        deadlock = Some(Deadlock {
+                            state: curr,
+                            trace: trace.to_vec(),
+                        });
+                    }
+                    break;
+                }
+            };
+
+            curr = transition.to.clone();
+            trace = trace.extend(transition);
+            check_invariants(&self.config.invariants, &curr, &trace, &mut violations);
+        }
+
+        SimulatedWalk {
+            seed,
+            trace: trace.to_vec(),
+            violations,
+            action_fault,
+            deadlock,
+        }
+    }
+
+    /// Takes the first enabled action with an outcome leaving `from`, trying
+    /// them in a random order, and one of those outcomes at random. Failed
+    /// outcomes are kept, rolled back or dropped following the failed action
+    /// policy, and an action that panics or times out on any of its outcomes
+    /// stops the walk instead, its fault rolled back, like in an exhaustive
+    /// exploration.
+    async fn step(&self, from: &GlobalState, rng: &mut SplitMix64) -> Step {
+        let mut order = (0..self.actions.len()).collect::<Vec<_>>();
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.next_below(i + 1));
+        }
+
+        for index in order {
+            let action_template = &self.actions[index];
+            if !action_template.is_enabled(from) {
+                continue;
+            }
+            let results = self
+                .action_template_executor
+                .execute(action_template.clone(), from.clone())
+                .await;
+            if let Some(result) = results
+                .iter()
+                .find(|result| result.action_result.is_fault())
+            {
+                return Step::Faulted(Transition {
+                    from: from.clone(),
+                    to: from.clone(),
+                    action_template: action_template.clone(),
+                    action_result: result.action_result.clone(),
+                    action_output: None,
+                });
+            }
+
+            let mut transitions = results
+                .into_iter()
+                .filter_map(|result| {
+                    let to = match &result.action_result {
+                        ActionResult::Failed(_) => match action_template
+                            .failed_action_policy
+                            .unwrap_or(self.config.execution.failed_action_policy)
+                        {
+                            FailedActionPolicy::KeepState => result.global_states,
+                            FailedActionPolicy::RollBack => from.clone(),
+                            FailedActionPolicy::Drop => return None,
+                        },
+                        _ => result.global_states,
+                    };
+                    (to != *from).then(|| Transition {
+                        from: from.clone(),
+                        to,
+                        action_template: action_template.clone(),
+                        action_result: result.action_result,
+                        action_output: result.action_output,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !transitions.is_empty() {
+                let index = match transitions.len() {
+                    1 => 0,
+                    len => rng.next_below(len),
+                };
+                return Step::Taken(transitions.swap_remove(index));
+            }
+        }
+
+        Step::Stuck
+    }
+}
+
+fn sorted(actions: HashSet<ActionTemplate>) -> Vec<ActionTemplate> {
+    let mut actions = actions
+        .iter()
+        .flat_map(ActionTemplate::expand)
+        .collect::<Vec<_>>();
+    actions.sort_by(|a, b| {
+        (&a.performer_id, &a.label, a.receiver_ids()).cmp(&(
+            &b.performer_id,
+            &b.label,
+            b.receiver_ids(),
+        ))
+    });
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use crate::{
+        action::{
+            ActionOutput, ActionResult, ActionTemplate, ActionTemplateExecutor, ExecutionConfig,
+            ExecutionResult, FailedActionPolicy,
+        },
+        global_state::GlobalState,
+        state_machine_driver::{
+            deadlock::DeadlockDetection,
+            invariant::{Invariant, ViolationPolicy},
+        },
+        test_utils::{
+            test_actors::TestActor1Error,
+            test_graph::{ACTOR_ID, action, global_state, value_of},
+        },
+    };
+
+    use test_case::test_case;
+
+    use super::{SimulationConfig, Simulator, sorted};
+
+    static INCREASE: &str = "increase";
+    static DOUBLE: &str = "double";
+    static FAIL: &str = "fail";
+    static SPILL: &str = "spill";
+    static PANIC: &str = "panic";
+
+    /// Increases or doubles the value of the test actor, fails with or
+    /// without having increased it by 5, or panics.
+    struct CounterExecutor;
+
+    #[async_trait::async_trait]
+    impl ActionTemplateExecutor for CounterExecutor {
+        async fn execute(
+            &self,
+            template: ActionTemplate,
+            global_state: GlobalState,
+        ) -> Vec<ExecutionResult> {
+            let value = value_of(&global_state);
+            vec![match template.label.as_str() {
+                "increase" => succeeded(value.saturating_add(1)),
+                "double" => succeeded(value.saturating_mul(2)),
+                "spill" => failed(value.saturating_add(5)),
+                "panic" => ExecutionResult {
+                    action_result: ActionResult::Panicked("boom".to_string()),
+                    action_output: None,
+                    global_states: global_state,
+                },
+                _ => failed(value),
+            }]
+        }
+    }
+
+    #[tokio::test]
+    async fn walks_are_bounded_by_trace_length() {
+        let simulator = simulator(&[INCREASE, DOUBLE, FAIL], config(7, 5, vec![]));
+
+        let walk = simulator.walk(global_state(0), 0).await;
+
+        assert_eq!(walk.trace.len(), 5);
+        assert_eq!(walk.trace.first().unwrap().from, global_state(0));
+        assert!(walk.trace.windows(2).all(|pair| pair[0].to == pair[1].from));
+        assert!(
+            walk.trace
+                .iter()
+                .all(|transition| transition.action_template.label != FAIL)
+        );
+    }
+
+    #[tokio::test]
+    async fn walk_ends_when_no_action_succeeds() {
+        let simulator = simulator(&[FAIL], config(7, 5, vec![]));
+
+        assert!(simulator.walk(global_state(0), 0).await.trace.is_empty());
+    }
+
+    #[tokio::test]
+    async fn walk_ends_when_every_action_is_disabled() {
+        let simulator = Simulator {
+            actions: vec![ActionTemplate {
+                guard: Some(Arc::new(|performer, _| {
+                    *performer != global_state(3).get_local_state(&ACTOR_ID)
+                })),
+                ..action(INCREASE)
+            }],
+            ..simulator(&[], config(7, 5, vec![]))
+        };
+
+        let walk = simulator.walk(global_state(0), 0).await;
+
+        assert_eq!(walk.trace.len(), 3);
+        assert_eq!(walk.trace.last().unwrap().to, global_state(3));
+    }
+
+    #[tokio::test]
+    async fn walk_ends_when_every_action_leads_back_to_the_state() {
+        let simulator = simulator(&[DOUBLE], config(7, 5, vec![]));
+
+        assert!(simulator.walk(global_state(0), 0).await.trace.is_empty());
+    }
+
+    #[test_case(None, None ; "not looked for")]
+    #[test_case(Some(3), None ; "terminal")]
+    #[test_case(Some(4), Some(3) ; "deadlocked")]
+    #[tokio::test]
+    async fn walks_getting_stuck_are_told_apart_from_terminal_ones(
+        terminal_value: Option<u8>,
+        expected_deadlock_value: Option<u8>,
+    ) {
+        let simulator = Simulator {
+            actions: vec![ActionTemplate {
+                guard: Some(Arc::new(|performer, _| {
+                    *performer != global_state(3).get_local_state(&ACTOR_ID)
+                })),
+                ..action(INCREASE)
+            }],
+            ..simulator(
+                &[],
+                SimulationConfig {
+                    deadlock_detection: terminal_value.map(|terminal_value| DeadlockDetection {
+                        is_terminal: Some(Arc::new(move |global_state| {
+                            value_of(global_state) == terminal_value
+                        })),
+                    }),
+                    ..config(7, 5, vec![])
+                },
+            )
+        };
+
+        let walk = simulator.walk(global_state(0), 0).await;
+
+        assert_eq!(
+            walk.deadlock
+                .as_ref()
+                .map(|deadlock| value_of(&deadlock.state)),
+            expected_deadlock_value
+        );
+        assert_eq!(walk.failed(), expected_deadlock_value.is_some());
+        if let Some(deadlock) = &walk.deadlock {
+            assert_eq!(deadlock.trace, walk.trace);
+            assert_eq!(deadlock.initial_state(), &global_state(0));
+        }
+    }
+
+    #[test_case(FailedActionPolicy::KeepState, None,                               true  ; "kept")]
+    #[test_case(FailedActionPolicy::RollBack,  None,                               false ; "rolled back")]
+    #[test_case(FailedActionPolicy::Drop,      None,                               false ; "dropped")]
+    #[test_case(FailedActionPolicy::RollBack,  Some(FailedActionPolicy::KeepState), true  ; "kept by the action")]
+    #[test_case(FailedActionPolicy::KeepState, Some(FailedActionPolicy::Drop),      false ; "dropped by the action")]
+    #[tokio::test]
+    async fn failed_actions_are_followed_as_their_policy_says(
+        failed_action_policy: FailedActionPolicy,
+        action_failed_action_policy: Option<FailedActionPolicy>,
+        followed: bool,
+    ) {
+        let simulator = Simulator {
+            actions: vec![ActionTemplate {
+                failed_action_policy: action_failed_action_policy,
+                ..action(SPILL)
+            }],
+            ..simulator(
+                &[],
+                SimulationConfig {
+                    execution: ExecutionConfig {
+                        failed_action_policy,
+                        ..ExecutionConfig::default()
+                    },
+                    ..config(7, 5, vec![invariant_value_below(5)])
+                },
+            )
+        };
+
+        let walk = simulator.walk(global_state(0), 0).await;
+
+        assert_eq!(walk.trace.len(), usize::from(followed));
+        assert_eq!(walk.violations.len(), usize::from(followed));
+        if followed {
+            assert!(!walk.trace[0].action_result.is_success());
+            assert_eq!(walk.violations[0].state, global_state(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_walks_are_reported_and_can_be_replayed() {
+        let simulator = simulator(
+            &[INCREASE, DOUBLE, FAIL],
+            config(7, 20, vec![invariant_value_below(10)]),
+        );
+
+        let result = simulator.run(global_state(1)).await;
+
+        assert_eq!(result.walk_count, 1);
+        assert_eq!(result.failed_walks.len(), 1);
+        let failed_walk = &result.failed_walks[0];
+        assert_eq!(failed_walk.violations.len(), 1);
+        assert!(value_of(&failed_walk.violations[0].state) >= 10);
+        assert_eq!(failed_walk.violations[0].trace, failed_walk.trace);
+
+        let replayed_walk = simulator.walk(global_state(1), failed_walk.seed).await;
+        assert_eq!(replayed_walk.trace, failed_walk.trace);
+    }
+
+    #[tokio::test]
+    async fn violations_reached_through_failed_actions_are_reported_and_can_be_replayed() {
+        // Only `spill` leaves the initial state, by failing halfway through.
+        let simulator = simulator(
+            &[DOUBLE, FAIL, SPILL],
+            config(7, 20, vec![invariant_value_below(5)]),
+        );
+
+        let result = simulator.run(global_state(0)).await;
+
+        assert_eq!(result.failed_walks.len(), 1);
+        let failed_walk = &result.failed_walks[0];
+        assert_eq!(failed_walk.trace.len(), 1);
+        assert_eq!(failed_walk.trace[0].action_template.label, SPILL);
+        assert!(!failed_walk.trace[0].action_result.is_success());
+        assert_eq!(failed_walk.violations[0].state, global_state(5));
+        assert_eq!(failed_walk.violations[0].trace, failed_walk.trace);
+
+        let replayed_walk = simulator.walk(global_state(0), failed_walk.seed).await;
+        assert_eq!(replayed_walk.trace, failed_walk.trace);
+    }
+
+    #[tokio::test]
+    async fn action_faults_end_the_walk_and_are_reported_with_its_trace() {
+        let simulator = simulator(&[INCREASE, PANIC], config(7, 20, vec![]));
+
+        let result = simulator.run(global_state(0)).await;
+
+        assert_eq!(result.walk_count, 1);
+        assert_eq!(result.failed_walks.len(), 1);
+        let failed_walk = &result.failed_walks[0];
+        assert!(failed_walk.trace.len() < 20);
+        let action_fault = failed_walk.action_fault.as_ref().unwrap();
+        assert_eq!(action_fault.transition.action_template.label, PANIC);
+        assert!(action_fault.transition.action_result.is_fault());
+        assert_eq!(
+            action_fault.transition.from,
+            failed_walk
+                .trace
+                .last()
+                .map_or(global_state(0), |transition| transition.to.clone())
+        );
+        assert_eq!(action_fault.transition.to, action_fault.transition.from);
+        assert_eq!(action_fault.trace, failed_walk.trace);
+        assert!(failed_walk.violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn every_walk_is_run_when_configured_to_continue() {
+        let simulator = simulator(
+            &[INCREASE, DOUBLE],
+            SimulationConfig {
+                violation_policy: ViolationPolicy::Continue,
+                ..config(7, 20, vec![invariant_value_below(10)])
+            },
+        );
+
+        let result = simulator.run(global_state(1)).await;
+
+        assert_eq!(result.walk_count, 4);
+        assert_eq!(result.failed_walks.len(), 4);
+        assert_eq!(
+            result
+                .failed_walks
+                .iter()
+                .map(|walk| walk.seed)
+                .collect::<HashSet<_>>()
+                .len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn same_seed_leads_to_same_walks() {
+        let simulator = simulator(&[INCREASE, DOUBLE, FAIL], config(7, 8, vec![]));
+
+        for seed in 0..10 {
+            assert_eq!(
+                simulator.walk(global_state(0), seed).await.trace,
+                simulator.walk(global_state(0), seed).await.trace
+            );
+        }
+    }
+
+    #[test]
+    fn actions_are_sorted_regardless_of_set_order() {
+        let labels = sorted(HashSet::from([
+            action(INCREASE),
+            action(DOUBLE),
+            action(FAIL),
+        ]))
+        .into_iter()
+        .map(|action| action.label)
+        .collect::<Vec<_>>();
+
+        assert_eq!(labels, vec![DOUBLE, FAIL, INCREASE]);
+    }
+
+    fn simulator(labels: &[&str], config: SimulationConfig) -> Simulator {
+        Simulator {
+            actions: sorted(labels.iter().map(|label| action(label)).collect()),
+            action_template_executor: Box::new(CounterExecutor),
+            config,
+        }
+    }
+
+    fn config(seed: u64, trace_length: usize, invariants: Vec<Invariant>) -> SimulationConfig {
+        SimulationConfig {
+            seed,
+            trace_length,
+            walk_count: 4,
+            invariants,
+            violation_policy: ViolationPolicy::StopAtFirstViolation,
+            deadlock_detection: None,
+            execution: ExecutionConfig::default(),
+        }
+    }
+
+    fn succeeded(value: u8) -> ExecutionResult {
+        ExecutionResult {
+            action_result: ActionResult::Succeeded,
+            action_output: Some(ActionOutput::new(())),
+            global_states: global_state(value),
+        }
+    }
+
+    fn failed(value: u8) -> ExecutionResult {
+        ExecutionResult {
+            action_result: ActionResult::Failed(Arc::new(TestActor1Error)),
+            action_output: None,
+            global_states: global_state(value),
+        }
+    }
+
+    fn invariant_value_below(bound: u8) -> Invariant {
+        Invariant {
+            name: format!("value is below {bound}"),
+            predicate: Arc::new(move |global_state| value_of(global_state) < bound),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+
+use crate::{actor, global_state::GlobalState};
+
+/// Groups of interchangeable actors. States that only differ by a permutation
+/// of the local states within a group are visited once.
+///
+/// This is only sound if the model and the properties checked on it treat the
+/// actors of a group alike, e.g. no actor state refers to the id of another
+/// actor of the group. Traces are still made of the concrete transitions
+/// explored, while liveness properties are only checked on the transitions out
+/// of one state per equivalence class.
+#[derive(Debug, Clone, Default)]
+pub struct Symmetry {
+    pub groups: Vec<Vec<actor::Id>>,
+}
+
+impl Symmetry {
+    /// Representative of the states equivalent to `global_state`, in which the
+    /// local states of every group are sorted in the order of the actor ids.
+    pub fn canonicalize<'a>(&self, global_state: &'a GlobalState) -> Cow<'a, GlobalState> {
+        let mut canonical = Cow::Borrowed(global_state);
+        for group in &self.groups {
+            let mut actor_ids = group.clone();
+            actor_ids.sort();
+            let mut local_states: Vec<_> = actor_ids
+                .iter()
+                .map(|actor_id| global_state.get_local_state(actor_id))
+                .collect();
+            local_states.sort();
+
+            for (actor_id, local_state) in actor_ids.into_iter().zip(local_states) {
+                if global_state.get_local_state(&actor_id) != local_state {
+                    canonical.to_mut().insert_local_state(actor_id, local_state);
+                }
+            }
+        }
+        canonical
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        collections::BTreeMap,
+        sync::{Arc, LazyLock},
+    };
+
+    use crate::{
+        actor::{self, local_state::LocalState},
+        global_state::GlobalState,
+        test_utils::test_actors::TestActor1State,
+    };
+
+    use super::Symmetry;
+
+    static ACTOR_1: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_1".to_string()));
+    static ACTOR_2: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_2".to_string()));
+    static ACTOR_3: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_3".to_string()));
+
+    fn global_state(values: [u8; 3]) -> GlobalState {
+        GlobalState::new(BTreeMap::from_iter(
+            [&*ACTOR_1, &*ACTOR_2, &*ACTOR_3]
+                .into_iter()
+                .zip(values)
+                .map(|(actor_id, value)| {
+                    (
+                        actor_id.clone(),
+                        LocalState {
+                            actor_state: Arc::new(TestActor1State { value }),
+                        },
+                    )
+                }),
+        ))
+    }
+
+    fn symmetry(group: &[&actor::Id]) -> Symmetry {
+        Symmetry {
+            groups: vec![group.iter().map(|actor_id| (*actor_id).clone()).collect()],
+        }
+    }
+
+    #[test]
+    fn permutations_within_a_group_are_merged() {
+        let symmetry = symmetry(&[&ACTOR_1, &ACTOR_2, &ACTOR_3]);
+
+        for values in [[2, 0, 1], [1, 2, 0], [0, 2, 1]] {
+            assert_eq!(
+                symmetry.canonicalize(&global_state(values)).into_owned(),
+                global_state([0, 1, 2])
+            );
+        }
+    }
+
+    #[test]
+    fn actors_outside_of_groups_are_left_alone() {
+        let symmetry = symmetry(&[&ACTOR_2, &ACTOR_3]);
+
+        assert_eq!(
+            symmetry.canonicalize(&global_state([2, 1, 0])).into_owned(),
+            global_state([2, 0, 1])
+        );
+        assert_ne!(
+            symmetry.canonicalize(&global_state([2, 1, 0])).into_owned(),
+            symmetry.canonicalize(&global_state([1, 2, 0])).into_owned()
+        );
+    }
+
+    #[test]
+    fn canonical_state_is_not_copied() {
+        let global_state = global_state([0, 1, 2]);
+
+        assert!(matches!(
+            symmetry(&[&ACTOR_1, &ACTOR_2, &ACTOR_3]).canonicalize(&global_state),
+            Cow::Borrowed(_)
+        ));
+    }
+}
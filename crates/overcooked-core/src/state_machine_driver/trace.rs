@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::transition::Transition;
+
+/// Path of transitions leading from an initial state to a discovered state.
+///
+/// Traces share their prefixes, so extending a trace is cheap and only the
+/// traces of states still waiting to be explored are kept alive.
+#[derive(Debug, Clone, Default)]
+pub struct Trace(Option<Arc<TraceNode>>);
+
+#[derive(Debug)]
+struct TraceNode {
+    transition: Transition,
+    previous: Trace,
+    len: usize,
+}
+
+impl Trace {
+    pub fn extend(&self, transition: Transition) -> Self {
+        Self(Some(Arc::new(TraceNode {
+            transition,
+            previous: self.clone(),
+            len: self.len() + 1,
+        })))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.as_ref().map_or(0, |node| node.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn last(&self) -> Option<&Transition> {
+        self.0.as_ref().map(|node| &node.transition)
+    }
+
+    /// The trace this one extends.
+    pub fn previous(&self) -> Option<&Trace> {
+        self.0.as_ref().map(|node| &node.previous)
+    }
+
+    /// Whether both traces are the same one, rather than equal ones.
+    pub fn ptr_eq(&self, other: &Trace) -> bool {
+        match (&self.0, &other.0) {
+            (Some(node), Some(other_node)) => Arc::ptr_eq(node, other_node),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<Transition> {
+        let mut transitions = Vec::with_capacity(self.len());
+        let mut curr = self;
+        while let Some(node) = &curr.0 {
+            transitions.push(node.transition.clone());
+            curr = &node.previous;
+        }
+        transitions.reverse();
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::test_graph::transition;
+
+    use super::Trace;
+
+    #[test]
+    fn empty_trace_has_no_transitions() {
+        let trace = Trace::default();
+
+        assert!(trace.is_empty());
+        assert_eq!(trace.len(), 0);
+        assert!(trace.to_vec().is_empty());
+    }
+
+    #[test]
+    fn transitions_are_kept_in_order() {
+        let trace = Trace::default()
+            .extend(transition(0, 1, "a"))
+            .extend(transition(1, 2, "b"));
+
+        assert_eq!(trace.len(), 2);
+        assert_eq!(
+            trace.to_vec(),
+            vec![transition(0, 1, "a"), transition(1, 2, "b")]
+        );
+    }
+
+    #[test]
+    fn traces_extending_the_same_one_share_it() {
+        let trace = Trace::default().extend(transition(0, 1, "a"));
+        let extended = trace.extend(transition(1, 2, "b"));
+
+        assert!(extended.previous().unwrap().ptr_eq(&trace));
+        assert!(!extended.ptr_eq(&trace.extend(transition(1, 2, "b"))));
+        assert_eq!(extended.last(), Some(&transition(1, 2, "b")));
+        assert!(Trace::default().previous().is_none());
+    }
+
+    #[test]
+    fn extending_a_trace_does_not_change_it() {
+        let trace = Trace::default().extend(transition(0, 1, "a"));
+        let _ = trace.extend(transition(1, 2, "b"));
+
+        assert_eq!(trace.to_vec(), vec![transition(0, 1, "a")]);
+    }
+}
@@ -0,0 +1,96 @@
+use std::{collections::HashSet, io};
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::{
+        disk_spill::DiskSpill,
+        visited_set::{
+            bitstate_visited_set::{BitstateReport, BitstateVisitedSet},
+            disk_visited_set::DiskVisitedSet,
+            fingerprint_visited_set::FingerprintVisitedSet,
+            full_visited_set::FullVisitedSet,
+        },
+    },
+};
+
+pub mod bitstate_visited_set;
+mod disk_visited_set;
+mod fingerprint_visited_set;
+mod full_visited_set;
+
+/// Remembers the states visited by a run along with the depth at which each
+/// of them was reached.
+pub trait VisitedSet: Send {
+    fn depth(&self, global_state: &GlobalState) -> Option<usize>;
+    fn insert(&mut self, global_state: &GlobalState, depth: usize);
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+
+    /// The visited states, as far as they are kept.
+    fn into_states(self: Box<Self>) -> HashSet<GlobalState>;
+
+    /// Estimated probability that two of the visited states were taken for
+    /// one another, leaving part of the state space unexplored.
+    fn collision_probability(&self) -> Option<f64> {
+        None
+    }
+
+    fn bitstate_report(&self) -> Option<BitstateReport> {
+        None
+    }
+
+    /// Whether [`VisitedSet::depth`] tells the actual depth, which searching
+    /// again states reached through shorter traces relies on.
+    fn keeps_depths(&self) -> bool {
+        true
+    }
+
+    /// The first error met while moving visited states to disk or reading
+    /// them back, since which the visited states may be wrong.
+    fn take_error(&mut self) -> Option<io::Error> {
+        None
+    }
+
+    fn contains(&self, global_state: &GlobalState) -> bool {
+        self.depth(global_state).is_some()
+    }
+}
+
+/// How visited states are remembered. Whichever is picked, the frontier and
+/// the traces leading to its states still hold full states.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VisitedSetType {
+    /// Keeps every visited state.
+    #[default]
+    Full,
+    /// Only keeps a 64 bit hash of every visited state.
+    Fingerprint64,
+    /// Only keeps a 128 bit hash of every visited state.
+    Fingerprint128,
+    /// Only sets `hash_functions` bits per visited state in a bit array of
+    /// `memory_bytes` bytes.
+    Bitstate {
+        memory_bytes: usize,
+        hash_functions: u32,
+    },
+    /// Only keeps a 64 bit hash of every visited state, spilled to disk past a
+    /// memory threshold.
+    Disk { disk_spill: DiskSpill },
+}
+
+impl VisitedSetType {
+    pub fn create(&self) -> Box<dyn VisitedSet> {
+        match self {
+            VisitedSetType::Full => Box::new(FullVisitedSet::default()),
+            VisitedSetType::Fingerprint64 => Box::new(FingerprintVisitedSet::<u64>::default()),
+            VisitedSetType::Fingerprint128 => Box::new(FingerprintVisitedSet::<u128>::default()),
+            VisitedSetType::Bitstate {
+                memory_bytes,
+                hash_functions,
+            } => Box::new(BitstateVisitedSet::new(*memory_bytes, *hash_functions)),
+            VisitedSetType::Disk { disk_spill } => {
+                Box::new(DiskVisitedSet::new(disk_spill.clone()))
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::visited_set::{VisitedSet, fingerprint_visited_set::seeded_hash},
+};
+
+/// Sets `hash_functions` bits of a fixed size bit array for every visited
+/// state, in the way of SPIN's supertrace mode. A state whose bits are all set
+/// already is taken as visited, so part of the state space may be missed, but
+/// memory use stays within the budget however large the model is.
+pub struct BitstateVisitedSet {
+    bits: Vec<u64>,
+    hash_functions: u32,
+    set_bits: usize,
+    inserted: usize,
+    /// Expected number of states taken as visited although they were not.
+    expected_omissions: f64,
+}
+
+/// How well a [`BitstateVisitedSet`] coped with the states of a run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitstateReport {
+    /// Share of the bits that are set.
+    pub fill_ratio: f64,
+    /// Estimated share of the reachable states that were actually explored.
+    pub estimated_coverage: f64,
+}
+
+impl BitstateVisitedSet {
+    pub fn new(memory_bytes: usize, hash_functions: u32) -> Self {
+        Self {
+            bits: vec![0; (memory_bytes / 8).max(1)],
+            hash_functions: hash_functions.max(1),
+            set_bits: 0,
+            inserted: 0,
+            expected_omissions: 0.0,
+        }
+    }
+
+    /// Positions of the bits of `global_state`, derived from two hashes by
+    /// double hashing.
+    fn positions(&self, global_state: &GlobalState) -> impl Iterator<Item = usize> {
+        let bit_count = (self.bits.len() * 64) as u64;
+        let first = seeded_hash(global_state, 0);
+        let second = seeded_hash(global_state, 1) | 1;
+        (0..u64::from(self.hash_functions))
+            .map(move |index| (first.wrapping_add(index.wrapping_mul(second)) % bit_count) as usize)
+    }
+
+    fn is_set(&self, position: usize) -> bool {
+        self.bits[position / 64] & (1 << (position % 64)) != 0
+    }
+
+    fn fill_ratio(&self) -> f64 {
+        self.set_bits as f64 / (self.bits.len() * 64) as f64
+    }
+
+    pub fn report(&self) -> BitstateReport {
+        BitstateReport {
+            fill_ratio: self.fill_ratio(),
+            estimated_coverage: if self.inserted == 0 {
+                1.0
+            } else {
+                self.inserted as f64 / (self.inserted as f64 + self.expected_omissions)
+            },
+        }
+    }
+}
+
+impl VisitedSet for BitstateVisitedSet {
+    /// Every visited state is reported at depth 0.
+    fn depth(&self, global_state: &GlobalState) -> Option<usize> {
+        self.positions(global_state)
+            .all(|position| self.is_set(position))
+            .then_some(0)
+    }
+
+    fn insert(&mut self, global_state: &GlobalState, _depth: usize) {
+        // Chance that a new state had every bit set already at this point,
+        // which adds that many omitted states per state actually inserted.
+        let false_positive = self.fill_ratio().powi(self.hash_functions as i32);
+        if false_positive < 1.0 {
+            self.expected_omissions += false_positive / (1.0 - false_positive);
+        }
+
+        let positions: Vec<usize> = self.positions(global_state).collect();
+        for position in positions {
+            if !self.is_set(position) {
+                self.bits[position / 64] |= 1 << (position % 64);
+                self.set_bits += 1;
+            }
+        }
+        self.inserted += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.inserted
+    }
+
+    fn clear(&mut self) {
+        self.bits.fill(0);
+        self.set_bits = 0;
+        self.inserted = 0;
+        self.expected_omissions = 0.0;
+    }
+
+    /// No state is kept.
+    fn into_states(self: Box<Self>) -> HashSet<GlobalState> {
+        HashSet::new()
+    }
+
+    fn keeps_depths(&self) -> bool {
+        false
+    }
+
+    fn bitstate_report(&self) -> Option<BitstateReport> {
+        Some(self.report())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_machine_driver::visited_set::VisitedSet, test_utils::test_graph::global_state,
+    };
+
+    use super::BitstateVisitedSet;
+
+    #[test]
+    fn inserted_states_are_visited() {
+        let mut visited_set = BitstateVisitedSet::new(1024, 3);
+        visited_set.insert(&global_state(0), 5);
+        visited_set.insert(&global_state(1), 6);
+
+        assert_eq!(visited_set.depth(&global_state(0)), Some(0));
+        assert!(visited_set.contains(&global_state(1)));
+        assert!(!visited_set.contains(&global_state(2)));
+        assert_eq!(visited_set.len(), 2);
+        assert!(!visited_set.keeps_depths());
+    }
+
+    #[test]
+    fn report_tracks_the_fill_ratio() {
+        let mut visited_set = BitstateVisitedSet::new(8, 2);
+        assert_eq!(visited_set.report().fill_ratio, 0.0);
+        assert_eq!(visited_set.report().estimated_coverage, 1.0);
+
+        for value in 0..10 {
+            visited_set.insert(&global_state(value), 0);
+        }
+        let report = visited_set.report();
+
+        assert!(report.fill_ratio > 0.0 && report.fill_ratio <= 20.0 / 64.0);
+        assert!(report.estimated_coverage < 1.0 && report.estimated_coverage > 0.5);
+    }
+
+    #[test]
+    fn crowded_bit_array_takes_new_states_as_visited() {
+        let mut visited_set = BitstateVisitedSet::new(0, 1);
+        for value in 0..128 {
+            visited_set.insert(&global_state(value), 0);
+        }
+
+        assert!((128..=u8::MAX).any(|value| visited_set.contains(&global_state(value))));
+        assert!(visited_set.report().estimated_coverage < 0.5);
+    }
+
+    #[test]
+    fn clearing_forgets_every_state() {
+        let mut visited_set = BitstateVisitedSet::new(64, 2);
+        visited_set.insert(&global_state(0), 0);
+        visited_set.clear();
+
+        assert!(!visited_set.contains(&global_state(0)));
+        assert_eq!(visited_set.len(), 0);
+        assert_eq!(visited_set.report().fill_ratio, 0.0);
+    }
+}
@@ -0,0 +1,307 @@
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+};
+
+use crate::{
+    global_state::GlobalState,
+    state_machine_driver::{
+        disk_spill::{DiskSpill, SpillDirectory},
+        visited_set::{
+            VisitedSet,
+            fingerprint_visited_set::{Fingerprint, collision_probability},
+        },
+    },
+};
+
+/// A fingerprint and a depth, both as little endian `u64`s.
+const RECORD_BYTES: usize = 16;
+/// Number of records read at once when looking a fingerprint up on disk.
+const BLOCK_RECORDS: usize = 64;
+
+/// Keeps a 64 bit fingerprint of every visited state, in the way of TLC's
+/// disk based fingerprint set. Fingerprints are kept in memory until they
+/// take more than the memory threshold, then merged into a file of sorted
+/// records, of which only the first fingerprint of every block stays in
+/// memory.
+///
+/// Once the spilled fingerprints cannot be written or read back, the states
+/// they stand for are taken as unvisited and the error is kept for
+/// [`VisitedSet::take_error`].
+pub struct DiskVisitedSet {
+    disk_spill: DiskSpill,
+    /// Only created once fingerprints are spilled.
+    directory: Option<SpillDirectory>,
+    /// Depths of the fingerprints not spilled yet.
+    depths: HashMap<u64, usize>,
+    /// Records sorted by fingerprint.
+    file: Option<File>,
+    record_count: usize,
+    /// First fingerprint of every block of records of `file`.
+    index: Vec<u64>,
+    /// The first spilling error, set from lookups as well.
+    error: OnceCell<io::Error>,
+}
+
+impl DiskVisitedSet {
+    pub fn new(disk_spill: DiskSpill) -> Self {
+        Self {
+            disk_spill,
+            directory: None,
+            depths: HashMap::new(),
+            file: None,
+            record_count: 0,
+            index: Vec::new(),
+            error: OnceCell::new(),
+        }
+    }
+
+    /// Position and depth of the record of `fingerprint` on disk.
+    fn find_on_disk(&self, fingerprint: u64) -> io::Result<Option<(usize, usize)>> {
+        let Some(file) = self.file.as_ref() else {
+            return Ok(None);
+        };
+        let Some(block) = self
+            .index
+            .partition_point(|first| *first <= fingerprint)
+            .checked_sub(1)
+        else {
+            return Ok(None);
+        };
+        let first_record = block * BLOCK_RECORDS;
+        let record_count = BLOCK_RECORDS.min(self.record_count - first_record);
+
+        let mut bytes = vec![0; record_count * RECORD_BYTES];
+        let mut file = file;
+        file.seek(SeekFrom::Start((first_record * RECORD_BYTES) as u64))?;
+        file.read_exact(&mut bytes)?;
+        let records: Vec<(u64, usize)> = bytes.chunks(RECORD_BYTES).map(decode).collect();
+        Ok(records
+            .binary_search_by_key(&fingerprint, |(fingerprint, _)| *fingerprint)
+            .ok()
+            .map(|position| (first_record + position, records[position].1)))
+    }
+
+    fn update_on_disk(&self, position: usize, depth: usize) -> io::Result<()> {
+        let mut file = self.file.as_ref().unwrap();
+        file.seek(SeekFrom::Start((position * RECORD_BYTES + 8) as u64))?;
+        file.write_all(&(depth as u64).to_le_bytes())
+    }
+
+    /// Same as [`DiskVisitedSet::find_on_disk`], keeping the error if any.
+    fn try_find_on_disk(&self, fingerprint: u64) -> Option<(usize, usize)> {
+        self.find_on_disk(fingerprint)
+            .unwrap_or_else(|error| self.keep(error))
+    }
+
+    fn keep<T: Default>(&self, error: io::Error) -> T {
+        let _ = self.error.set(error);
+        T::default()
+    }
+
+    /// Merges the fingerprints kept in memory into the file.
+    fn spill(&mut self) -> io::Result<()> {
+        if self.directory.is_none() {
+            self.directory = Some(self.disk_spill.create_directory("visited")?);
+        }
+        let directory = self.directory.as_ref().unwrap();
+        let path = directory.file("fingerprints");
+        let merged_path = directory.file("fingerprints.merged");
+
+        let mut in_memory: Vec<(u64, usize)> = self.depths.drain().collect();
+        in_memory.sort_unstable();
+        let mut on_disk = match self.file.take() {
+            Some(mut file) => {
+                file.rewind()?;
+                Some(BufReader::new(file))
+            }
+            None => None,
+        };
+        let mut next_on_disk = || {
+            let mut record = [0; RECORD_BYTES];
+            on_disk.as_mut()?.read_exact(&mut record).ok()?;
+            Some(decode(&record))
+        };
+
+        let mut writer = BufWriter::new(File::create(&merged_path)?);
+        let mut in_memory = in_memory.into_iter().peekable();
+        let mut disk_record = next_on_disk();
+        self.index.clear();
+        self.record_count = 0;
+        loop {
+            let record = match (in_memory.peek(), disk_record) {
+                (Some(memory_record), Some(record)) if memory_record.0 < record.0 => {
+                    in_memory.next().unwrap()
+                }
+                (_, Some(record)) => {
+                    disk_record = next_on_disk();
+                    record
+                }
+                (Some(_), None) => in_memory.next().unwrap(),
+                (None, None) => break,
+            };
+            if self.record_count.is_multiple_of(BLOCK_RECORDS) {
+                self.index.push(record.0);
+            }
+            writer.write_all(&record.0.to_le_bytes())?;
+            writer.write_all(&(record.1 as u64).to_le_bytes())?;
+            self.record_count += 1;
+        }
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(&merged_path, &path)?;
+        self.file = Some(OpenOptions::new().read(true).write(true).open(&path)?);
+        Ok(())
+    }
+}
+
+fn decode(record: &[u8]) -> (u64, usize) {
+    let (fingerprint, depth) = record.split_at(8);
+    (
+        u64::from_le_bytes(fingerprint.try_into().unwrap()),
+        u64::from_le_bytes(depth.try_into().unwrap()) as usize,
+    )
+}
+
+impl VisitedSet for DiskVisitedSet {
+    fn depth(&self, global_state: &GlobalState) -> Option<usize> {
+        let fingerprint = u64::of(global_state);
+        match self.depths.get(&fingerprint) {
+            Some(depth) => Some(*depth),
+            None => self.try_find_on_disk(fingerprint).map(|(_, depth)| depth),
+        }
+    }
+
+    fn insert(&mut self, global_state: &GlobalState, depth: usize) {
+        let fingerprint = u64::of(global_state);
+        if !self.depths.contains_key(&fingerprint)
+            && let Some((position, _)) = self.try_find_on_disk(fingerprint)
+        {
+            if let Err(error) = self.update_on_disk(position, depth) {
+                self.keep(error)
+            }
+            return;
+        }
+
+        self.depths.insert(fingerprint, depth);
+        if self.depths.len() * RECORD_BYTES > self.disk_spill.memory_threshold
+            && let Err(error) = self.spill()
+        {
+            self.keep(error)
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.depths.len() + self.record_count
+    }
+
+    fn clear(&mut self) {
+        self.depths.clear();
+        self.file = None;
+        self.record_count = 0;
+        self.index.clear();
+        self.directory = None;
+    }
+
+    /// No state is kept.
+    fn into_states(self: Box<Self>) -> HashSet<GlobalState> {
+        HashSet::new()
+    }
+
+    fn collision_probability(&self) -> Option<f64> {
+        Some(collision_probability(
+            self.len(),
+            <u64 as Fingerprint>::BITS,
+        ))
+    }
+
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        state_machine_driver::{disk_spill::DiskSpill, visited_set::VisitedSet},
+        test_utils::test_graph::global_state,
+    };
+
+    use super::{BLOCK_RECORDS, DiskVisitedSet, RECORD_BYTES};
+
+    fn visited_set(memory_threshold: usize) -> DiskVisitedSet {
+        DiskVisitedSet::new(DiskSpill {
+            directory: env::temp_dir(),
+            memory_threshold,
+        })
+    }
+
+    #[test]
+    fn spilled_states_are_still_visited() {
+        let mut visited_set = visited_set(4 * RECORD_BYTES);
+        for value in 0..=u8::MAX {
+            visited_set.insert(&global_state(value), value.into());
+        }
+
+        assert!(visited_set.depths.len() <= 4);
+        assert!(visited_set.index.len() > 1);
+        assert_eq!(
+            visited_set.index.len(),
+            visited_set.record_count.div_ceil(BLOCK_RECORDS)
+        );
+        assert_eq!(visited_set.len(), 256);
+        for value in 0..=u8::MAX {
+            assert_eq!(
+                visited_set.depth(&global_state(value)),
+                Some(value.into()),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn depths_are_updated_on_disk() {
+        let mut visited_set = visited_set(0);
+        visited_set.insert(&global_state(0), 5);
+        visited_set.insert(&global_state(1), 6);
+        visited_set.insert(&global_state(0), 2);
+
+        assert!(visited_set.depths.is_empty());
+        assert_eq!(visited_set.depth(&global_state(0)), Some(2));
+        assert_eq!(visited_set.depth(&global_state(1)), Some(6));
+        assert!(!visited_set.contains(&global_state(2)));
+        assert_eq!(visited_set.len(), 2);
+    }
+
+    #[test]
+    fn clearing_removes_the_spilled_states() {
+        let mut visited_set = visited_set(0);
+        visited_set.insert(&global_state(0), 0);
+        let directory = visited_set.directory.as_ref().unwrap().path().to_path_buf();
+        visited_set.clear();
+
+        assert!(!directory.exists());
+        assert!(!visited_set.contains(&global_state(0)));
+        assert_eq!(visited_set.len(), 0);
+    }
+
+    #[test]
+    fn spilling_errors_are_kept() {
+        let file = env::temp_dir().join(format!("not-a-directory-visited-{}", std::process::id()));
+        fs::write(&file, []).unwrap();
+        let mut visited_set = DiskVisitedSet::new(DiskSpill {
+            directory: file.clone(),
+            memory_threshold: 0,
+        });
+        visited_set.insert(&global_state(0), 0);
+
+        assert!(visited_set.take_error().is_some());
+        assert!(visited_set.take_error().is_none());
+        fs::remove_file(file).unwrap();
+    }
+}
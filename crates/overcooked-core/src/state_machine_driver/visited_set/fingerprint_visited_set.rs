@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{global_state::GlobalState, state_machine_driver::visited_set::VisitedSet};
+
+/// Keeps a fixed size hash of every visited state, computed through the
+/// [`DynHash`](dyn_hash::DynHash) implementations of the actor states, rather
+/// than the state itself. A state whose fingerprint collides with the one of
+/// a visited state is wrongly taken as visited.
+///
+/// Only the visited set shrinks: the frontier, the traces leading to its
+/// states and the transitions of the result still hold full states.
+#[derive(Default)]
+pub struct FingerprintVisitedSet<F> {
+    depths: HashMap<F, usize>,
+}
+
+/// A hash of a [`GlobalState`] made of [`Fingerprint::BITS`] bits.
+pub trait Fingerprint: Copy + Eq + Hash + Send {
+    const BITS: i32;
+
+    fn of(global_state: &GlobalState) -> Self;
+}
+
+impl Fingerprint for u64 {
+    const BITS: i32 = 64;
+
+    fn of(global_state: &GlobalState) -> Self {
+        seeded_hash(global_state, 0)
+    }
+}
+
+impl Fingerprint for u128 {
+    const BITS: i32 = 128;
+
+    fn of(global_state: &GlobalState) -> Self {
+        (u128::from(seeded_hash(global_state, 0)) << 64) | u128::from(seeded_hash(global_state, 1))
+    }
+}
+
+/// Hash of `global_state` that differs for every `seed`.
+pub fn seeded_hash(global_state: &GlobalState, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(seed);
+    global_state.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<F: Fingerprint> VisitedSet for FingerprintVisitedSet<F> {
+    fn depth(&self, global_state: &GlobalState) -> Option<usize> {
+        self.depths.get(&F::of(global_state)).copied()
+    }
+
+    fn insert(&mut self, global_state: &GlobalState, depth: usize) {
+        self.depths.insert(F::of(global_state), depth);
+    }
+
+    fn len(&self) -> usize {
+        self.depths.len()
+    }
+
+    fn clear(&mut self) {
+        self.depths.clear();
+    }
+
+    /// No state is kept.
+    fn into_states(self: Box<Self>) -> HashSet<GlobalState> {
+        HashSet::new()
+    }
+
+    /// Probability that any two of the visited states share a fingerprint.
+    fn collision_probability(&self) -> Option<f64> {
+        Some(collision_probability(self.depths.len(), F::BITS))
+    }
+}
+
+/// Probability that any two of `count` uniformly distributed fingerprints of
+/// `bits` bits are equal.
+pub fn collision_probability(count: usize, bits: i32) -> f64 {
+    let count = count as f64;
+    let expected_collisions = count * (count - 1.0) / 2f64.powi(bits + 1);
+    -(-expected_collisions).exp_m1()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state_machine_driver::visited_set::VisitedSet, test_utils::test_graph::global_state,
+    };
+
+    use super::{Fingerprint, FingerprintVisitedSet, seeded_hash};
+
+    #[test]
+    fn visited_states_are_told_apart_by_fingerprint() {
+        let mut visited_set = Box::new(FingerprintVisitedSet::<u64>::default());
+        visited_set.insert(&global_state(0), 0);
+        visited_set.insert(&global_state(1), 1);
+
+        assert_eq!(visited_set.depth(&global_state(0)), Some(0));
+        assert_eq!(visited_set.depth(&global_state(1)), Some(1));
+        assert!(!visited_set.contains(&global_state(2)));
+        assert_eq!(visited_set.len(), 2);
+        assert!(visited_set.into_states().is_empty());
+    }
+
+    #[test]
+    fn fingerprints_only_depend_on_local_states() {
+        assert_eq!(u128::of(&global_state(3)), u128::of(&global_state(3)));
+        assert_ne!(u128::of(&global_state(3)), u128::of(&global_state(4)));
+        assert_ne!(
+            seeded_hash(&global_state(3), 0),
+            seeded_hash(&global_state(3), 1)
+        );
+    }
+
+    #[test]
+    fn collision_probability_grows_with_the_number_of_states() {
+        let mut visited_set = FingerprintVisitedSet::<u64>::default();
+        assert_eq!(visited_set.collision_probability(), Some(0.0));
+
+        visited_set.insert(&global_state(0), 0);
+        visited_set.insert(&global_state(1), 0);
+        let two_states = visited_set.collision_probability().unwrap();
+        visited_set.insert(&global_state(2), 0);
+        let three_states = visited_set.collision_probability().unwrap();
+
+        assert!(two_states > 0.0);
+        assert!(three_states > two_states);
+        assert!((three_states - 3.0 / 2f64.powi(64)).abs() < 1e-25);
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{global_state::GlobalState, state_machine_driver::visited_set::VisitedSet};
+
+/// Keeps a copy of every visited state, which makes it exact at the cost of
+/// memory.
+#[derive(Default)]
+pub struct FullVisitedSet {
+    depths: HashMap<GlobalState, usize>,
+}
+
+impl VisitedSet for FullVisitedSet {
+    fn depth(&self, global_state: &GlobalState) -> Option<usize> {
+        self.depths.get(global_state).copied()
+    }
+
+    fn insert(&mut self, global_state: &GlobalState, depth: usize) {
+        self.depths.insert(global_state.clone(), depth);
+    }
+
+    fn len(&self) -> usize {
+        self.depths.len()
+    }
+
+    fn clear(&mut self) {
+        self.depths.clear();
+    }
+
+    fn into_states(self: Box<Self>) -> HashSet<GlobalState> {
+        self.depths.into_keys().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        state_machine_driver::visited_set::VisitedSet, test_utils::test_graph::global_state,
+    };
+
+    use super::FullVisitedSet;
+
+    #[test]
+    fn visited_states_are_kept_with_their_depth() {
+        let mut visited_set = Box::new(FullVisitedSet::default());
+        visited_set.insert(&global_state(0), 0);
+        visited_set.insert(&global_state(1), 3);
+        visited_set.insert(&global_state(1), 1);
+
+        assert_eq!(visited_set.depth(&global_state(1)), Some(1));
+        assert!(!visited_set.contains(&global_state(2)));
+        assert_eq!(visited_set.len(), 2);
+        assert_eq!(visited_set.collision_probability(), None);
+        assert_eq!(
+            visited_set.into_states(),
+            HashSet::from([global_state(0), global_state(1)])
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod test_actors;
+pub mod test_graph;
@@ -5,7 +5,8 @@ use std::sync::{
 
 use crate::{
     actor::{ActorBase, actor_state::ActorState},
-    impl_actor_base, impl_actor_state, intransitive_action, transitive_action,
+    impl_actor_base, impl_actor_state, intransitive_action, multi_receiver_action,
+    transitive_action,
 };
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
@@ -35,7 +36,13 @@ impl_actor_state!(TestActor1State);
 impl_actor_state!(TestActor2State);
 
 intransitive_action!(TestActor1, increase_inner_value_by_one);
+intransitive_action!(TestActor1, read_value);
 transitive_action!(TestActor1, decrease_test_actor_2_value_by_one, TestActor2);
+multi_receiver_action!(
+    TestActor1,
+    decrease_every_test_actor_2_value_by_one,
+    TestActor2
+);
 
 impl TestActor1 {
     pub fn new(value: u8) -> Self {
@@ -60,11 +67,27 @@ impl TestActor1 {
         Ok(())
     }
 
+    pub async fn decrease_every_test_actor_2_value_by_one(
+        &self,
+        test_actor_2s: &[&TestActor2],
+    ) -> Result<(), TestActor1Error> {
+        for test_actor_2 in test_actor_2s {
+            self.decrease_test_actor_2_value_by_one(test_actor_2)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn increase_inner_value_by_one(&self) -> Result<(), TestActor1Error> {
         self.value.fetch_add(1, Ordering::Relaxed);
 
         Ok(())
     }
+
+    pub async fn read_value(&self) -> Result<u8, TestActor1Error> {
+        Ok(self.get_value())
+    }
 }
 
 impl PartialEq for TestActor1 {
@@ -0,0 +1,173 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    error::Error,
+    sync::{Arc, LazyLock},
+};
+
+use crate::{
+    action::{ActionOutput, ActionResult, ActionTemplate, ActionType},
+    actor::{self, ActorBase, actor_state::ActorState, local_state::LocalState},
+    global_state::GlobalState,
+    state_machine_driver::{
+        TransitionComputer,
+        checkpoint::{ActionOutputCodec, LocalStateCodec},
+    },
+    test_utils::test_actors::{TestActor1Error, TestActor1State},
+    transition::Transition,
+};
+
+pub static ACTOR_ID: LazyLock<actor::Id> = LazyLock::new(|| actor::Id("actor_1".to_string()));
+
+/// A [`TransitionComputer`] that looks transitions up in a fixed graph instead
+/// of executing actions.
+pub struct GraphTransitionComputer {
+    transitions: HashMap<GlobalState, HashSet<Transition>>,
+}
+
+impl GraphTransitionComputer {
+    pub fn new(transitions: impl IntoIterator<Item = Transition>) -> Self {
+        let mut graph: HashMap<GlobalState, HashSet<Transition>> = HashMap::new();
+        for transition in transitions {
+            graph
+                .entry(transition.from.clone())
+                .or_default()
+                .insert(transition);
+        }
+        Self { transitions: graph }
+    }
+}
+
+#[async_trait::async_trait]
+impl TransitionComputer for GraphTransitionComputer {
+    async fn compute(&self, from: GlobalState) -> HashSet<Transition> {
+        self.transitions.get(&from).cloned().unwrap_or_default()
+    }
+
+    fn action_templates(&self) -> Vec<ActionTemplate> {
+        let action_templates: HashSet<&ActionTemplate> = self
+            .transitions
+            .values()
+            .flatten()
+            .map(|transition| &transition.action_template)
+            .collect();
+        action_templates.into_iter().cloned().collect()
+    }
+}
+
+/// A [`GlobalState`] of a single [`TestActor1State`] holding `value`.
+pub fn global_state(value: u8) -> GlobalState {
+    GlobalState::new(BTreeMap::from([(
+        ACTOR_ID.clone(),
+        LocalState {
+            actor_state: Arc::new(TestActor1State { value }),
+        },
+    )]))
+}
+
+/// A [`LocalStateCodec`] of the local states of [`global_state`].
+pub struct TestActor1StateCodec;
+
+impl LocalStateCodec for TestActor1StateCodec {
+    fn encode(&self, _: &actor::Id, local_state: &LocalState) -> Vec<u8> {
+        let actor_state = ActorState::as_any(local_state.actor_state.as_ref())
+            .downcast_ref::<TestActor1State>()
+            .unwrap();
+        vec![actor_state.value]
+    }
+
+    fn decode(&self, _: &actor::Id, bytes: &[u8]) -> Option<LocalState> {
+        let [value] = bytes else {
+            return None;
+        };
+        Some(LocalState {
+            actor_state: Arc::new(TestActor1State { value: *value }),
+        })
+    }
+}
+
+/// An [`ActionOutputCodec`] of the outputs and errors of [`transition`] and
+/// [`failed_transition`].
+pub struct TestOutputCodec;
+
+impl ActionOutputCodec for TestOutputCodec {
+    fn encode_output(&self, _: &ActionTemplate, action_output: &ActionOutput) -> Vec<u8> {
+        action_output.downcast_ref::<()>().unwrap();
+        vec![]
+    }
+
+    fn decode_output(&self, _: &ActionTemplate, bytes: &[u8]) -> Option<ActionOutput> {
+        bytes.is_empty().then(|| ActionOutput::new(()))
+    }
+
+    fn encode_error(
+        &self,
+        _: &ActionTemplate,
+        error: &(dyn Error + Send + Sync + 'static),
+    ) -> Vec<u8> {
+        error.downcast_ref::<TestActor1Error>().unwrap();
+        vec![]
+    }
+
+    fn decode_error(
+        &self,
+        _: &ActionTemplate,
+        bytes: &[u8],
+    ) -> Option<Arc<dyn Error + Send + Sync>> {
+        bytes
+            .is_empty()
+            .then(|| Arc::new(TestActor1Error) as Arc<dyn Error + Send + Sync>)
+    }
+}
+
+pub fn value_of(global_state: &GlobalState) -> u8 {
+    ActorState::as_any(global_state.get_local_state(&ACTOR_ID).actor_state.as_ref())
+        .downcast_ref::<TestActor1State>()
+        .unwrap()
+        .value
+}
+
+/// A successful transition between two states created by [`global_state`].
+pub fn transition(from: u8, to: u8, action_label: &str) -> Transition {
+    Transition {
+        from: global_state(from),
+        to: global_state(to),
+        action_template: action(action_label),
+        action_result: ActionResult::Succeeded,
+        action_output: Some(ActionOutput::new(())),
+    }
+}
+
+/// Same as [`transition`], except that its action failed.
+pub fn failed_transition(from: u8, to: u8, action_label: &str) -> Transition {
+    Transition {
+        action_result: ActionResult::Failed(Arc::new(TestActor1Error)),
+        action_output: None,
+        ..transition(from, to, action_label)
+    }
+}
+
+/// Same as [`transition`], except that its action panicked, which leaves the
+/// state as it was.
+pub fn panicked_transition(state: u8, action_label: &str) -> Transition {
+    Transition {
+        action_result: ActionResult::Panicked("boom".to_string()),
+        action_output: None,
+        ..transition(state, state, action_label)
+    }
+}
+
+pub fn action(label: &str) -> ActionTemplate {
+    ActionTemplate::new(
+        ACTOR_ID.clone(),
+        label.to_string(),
+        ActionType::Intransitive(Arc::new(|actor| {
+            Box::pin(proxy_for_intransitive_action(actor))
+        })),
+    )
+}
+
+async fn proxy_for_intransitive_action(
+    _: Arc<dyn ActorBase>,
+) -> Result<ActionOutput, Box<dyn Error + Send + Sync>> {
+    Ok(ActionOutput::new(()))
+}
@@ -1,14 +1,23 @@
+use std::sync::Arc;
+
 use crate::{
-    action::{ActionResult, ActionTemplate},
+    action::{ActionOutput, ActionResult, ActionTemplate},
     global_state::GlobalState,
 };
 
+pub type TransitionPredicate = Arc<dyn Fn(&Transition) -> bool + Send + Sync>;
+
 #[derive(Debug, Clone)]
 pub struct Transition {
     pub from: GlobalState,
     pub to: GlobalState,
     pub action_template: ActionTemplate,
     pub action_result: ActionResult,
+    /// What the action returned, unless it failed. Transitions of an action
+    /// between the same states are told apart by their result and output, so
+    /// that every outcome the action [`choose`](crate::action::choose)s is
+    /// kept.
+    pub action_output: Option<ActionOutput>,
 }
 
 impl std::hash::Hash for Transition {
@@ -16,6 +25,8 @@ impl std::hash::Hash for Transition {
         self.from.hash(state);
         self.to.hash(state);
         self.action_template.hash(state);
+        self.action_result.hash(state);
+        self.action_output.hash(state);
     }
 }
 
@@ -24,6 +35,8 @@ impl PartialEq for Transition {
         self.from == other.from
             && self.to == other.to
             && self.action_template == other.action_template
+            && self.action_result == other.action_result
+            && self.action_output == other.action_output
     }
 }
 